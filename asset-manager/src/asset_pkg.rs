@@ -20,18 +20,30 @@ pub struct AssetPkg {
 }
 
 impl AssetPkg {
+    /// Open a package, memory-mapping it when possible and falling back to
+    /// seek+read if the map fails.
     pub fn open(path: impl AsRef<Path>) -> Result<Self, AssetError> {
-        let path = path.as_ref();
+        Self::open_with(path.as_ref(), true)
+    }
+
+    /// Open a package without mapping it; every read copies from the file.
+    pub fn open_read(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        Self::open_with(path.as_ref(), false)
+    }
+
+    fn open_with(path: &Path, map: bool) -> Result<Self, AssetError> {
         let file = File::open(path)?;
         let file_len = file.metadata()?.len();
 
-        let backend = if file_len > 0 {
+        let backend = if file_len == 0 {
+            return Err(AssetError::InvalidPackage("empty file".into()));
+        } else if map {
             match unsafe { Mmap::map(&file) } {
                 Ok(m) => MappedOrFile::Mmap(m),
                 Err(_) => MappedOrFile::File(file),
             }
         } else {
-            return Err(AssetError::InvalidPackage("empty file".into()));
+            MappedOrFile::File(file)
         };
 
         // Read header serialized with bincode (use bincode for header decoding)
@@ -152,11 +164,7 @@ impl AssetPkg {
             return Err(AssetError::ResourceLimit("asset too large".into()));
         }
         match &self.backend {
-            MappedOrFile::Mmap(m) => {
-                let start = ent.offset as usize;
-                let end = (ent.offset + ent.size) as usize;
-                Ok(std::borrow::Cow::Borrowed(&m[start..end]))
-            },
+            MappedOrFile::Mmap(m) => Ok(std::borrow::Cow::Borrowed(mapped_entry(m, ent)?)),
             MappedOrFile::File(ref f) => {
                 let mut f = f.try_clone()?;
                 let mut buf = vec![0u8; ent.size as usize];
@@ -167,17 +175,18 @@ impl AssetPkg {
         }
     }
 
+    /// Whether asset reads are served from a memory map.
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.backend, MappedOrFile::Mmap(_))
+    }
+
     pub fn read_asset_bytes(&self, name: &str) -> Result<Vec<u8>, AssetError> {
         let ent = self.entries.get(name).ok_or(AssetError::NotFound)?;
         if ent.size > (1u64 << 31) {
             return Err(AssetError::ResourceLimit("asset too large".into()));
         }
         match &self.backend {
-            MappedOrFile::Mmap(m) => {
-                let start = ent.offset as usize;
-                let end = (ent.offset + ent.size) as usize;
-                Ok(m[start..end].to_vec())
-            },
+            MappedOrFile::Mmap(m) => Ok(mapped_entry(m, ent)?.to_vec()),
             MappedOrFile::File(ref f) => {
                 let mut f = f.try_clone()?;
                 let mut buf = vec![0u8; ent.size as usize];
@@ -207,3 +216,13 @@ impl AssetPkg {
     }
 }
 
+/// Slice `ent` out of the map, checked against the mapped length rather than
+/// trusting the index (and without truncating offsets on 32-bit targets).
+fn mapped_entry<'a>(m: &'a Mmap, ent: &AssetIndexEntry) -> Result<&'a [u8], AssetError> {
+    let start = usize::try_from(ent.offset).ok();
+    let end = usize::try_from(ent.offset.saturating_add(ent.size)).ok();
+    start
+        .zip(end)
+        .and_then(|(start, end)| m.get(start..end))
+        .ok_or_else(|| AssetError::InvalidPackage(format!("asset OOB: {}", ent.name)))
+}
@@ -16,15 +16,27 @@ pub struct SfxBlob {
     pub loop_points: Option<(u64, u64)>,
 }
 
+/// `SFX2` flag bit: a loop section (u64 start, u64 end, in frames) follows the
+/// 20-byte base header.
+pub const SFX2_FLAG_LOOP: u8 = 0x01;
+
 impl SfxBlob {
+    /// Parse an `SFX1` or `SFX2` blob.
+    ///
+    /// Both share the 20-byte header `magic(4) format(1) channels(1) byte6(1)
+    /// reserved(1) sample_rate(4) frames(8)`. In `SFX1` byte 6 is reserved; in
+    /// `SFX2` it holds flags, and with `SFX2_FLAG_LOOP` set a `[start, end)`
+    /// loop region follows before the samples. `SFX1` blobs never loop.
     pub fn from_sfx_bytes(bytes: &[u8]) -> Result<Self, AssetError> {
         if bytes.len() < 20 {
             return Err(AssetError::Decode("sfx too small".into()));
         }
         let magic = &bytes[0..4];
-        if magic != b"SFX1" {
-            return Err(AssetError::Decode("bad sfx magic".into()));
-        }
+        let flags = match magic {
+            b"SFX1" => 0u8,
+            b"SFX2" => bytes[6],
+            _ => return Err(AssetError::Decode("bad sfx magic".into())),
+        };
         let sf = bytes[4];
         let channels = bytes[5] as u16;
         let sample_rate = u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
@@ -35,6 +47,27 @@ impl SfxBlob {
             return Err(AssetError::ResourceLimit("sfx frame count unreasonable".into()));
         }
 
+        let mut header_len = 20usize;
+        let mut loop_points = None;
+        if flags & SFX2_FLAG_LOOP != 0 {
+            if bytes.len() < 36 {
+                return Err(AssetError::Decode("sfx loop section truncated".into()));
+            }
+            let mut b = [0u8; 8];
+            b.copy_from_slice(&bytes[20..28]);
+            let start = u64::from_le_bytes(b);
+            b.copy_from_slice(&bytes[28..36]);
+            let end = u64::from_le_bytes(b);
+            if start >= end || end > frames {
+                return Err(AssetError::Decode(format!(
+                    "invalid sfx loop region {}..{} for {} frames",
+                    start, end, frames
+                )));
+            }
+            loop_points = Some((start, end));
+            header_len = 36;
+        }
+
         let sample_format = match sf {
             0 => SampleFormat::F32,
             1 => SampleFormat::S16,
@@ -52,15 +85,15 @@ impl SfxBlob {
             .checked_mul(channels as usize)
             .and_then(|n| n.checked_mul(bytes_per_sample))
             .ok_or_else(|| AssetError::ResourceLimit("overflow computing sfx size".into()))?;
-        if bytes.len() < 20 + expected {
+        if bytes.len() < header_len + expected {
             return Err(AssetError::Decode("file truncated".into()));
         }
 
         let mut samples = Vec::with_capacity((frames as usize) * channels as usize);
-        let mut idx = 20usize;
+        let mut idx = header_len;
         match sample_format {
             SampleFormat::F32 => {
-                while idx + 4 <= 20 + expected {
+                while idx + 4 <= header_len + expected {
                     let mut b = [0u8;4];
                     b.copy_from_slice(&bytes[idx..idx+4]);
                     samples.push(f32::from_le_bytes(b));
@@ -68,7 +101,7 @@ impl SfxBlob {
                 }
             }
             SampleFormat::S16 => {
-                while idx + 2 <= 20 + expected {
+                while idx + 2 <= header_len + expected {
                     let mut b = [0u8;2];
                     b.copy_from_slice(&bytes[idx..idx+2]);
                     let v = i16::from_le_bytes(b) as f32 / i16::MAX as f32;
@@ -77,7 +110,7 @@ impl SfxBlob {
                 }
             }
             SampleFormat::U8 => {
-                while idx < 20 + expected {
+                while idx < header_len + expected {
                     let v = bytes[idx] as f32 / 255.0 * 2.0 - 1.0;
                     samples.push(v);
                     idx += 1;
@@ -90,7 +123,7 @@ impl SfxBlob {
             sample_rate,
             channels,
             frames,
-            loop_points: None,
+            loop_points,
         })
     }
 }
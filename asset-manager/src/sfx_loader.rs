@@ -26,8 +26,18 @@ pub fn load_sfx_path_with_target(
                 target_sample_rate,
                 meta.channels as usize,
             );
+            let out_frames = (samples.len() / (meta.channels as usize).max(1)) as u64;
+            let ratio = target_sample_rate as f64 / meta.sample_rate as f64;
+            // Loop points are stored in source-rate frames; move them onto the
+            // resampled timeline so playback wraps at the same musical point.
+            let loop_points = meta.loop_points.map(|(start, end)| {
+                let end = ((end as f64 * ratio).round() as u64).min(out_frames);
+                let start = ((start as f64 * ratio).round() as u64).min(end.saturating_sub(1));
+                (start, end)
+            });
             let meta = SfxMetadata {
                 sample_rate: target_sample_rate,
+                loop_points,
                 ..meta
             };
             return Ok((samples, meta));
@@ -66,7 +76,23 @@ pub fn load_sfx_path_with_target(
 /// Parse our simple .pcm/.sfx format. Backwards compatible: if only 8-byte header is present
 /// we parse channels/sample_rate and samples; if extended header includes loop points (24 bytes)
 /// we parse them as well.
+///
+/// Packed `SFX1`/`SFX2` blobs (as written by asset-utils) are recognised by their magic and
+/// decoded according to their format byte (f32 or i16), including any `SFX2` loop region.
 pub fn parse_pcm_sfx_data(data: &[u8]) -> Result<(Vec<f32>, SfxMetadata), Error> {
+    if data.starts_with(b"SFX1") || data.starts_with(b"SFX2") {
+        let blob = crate::sfx::SfxBlob::from_sfx_bytes(data)
+            .map_err(|e| Error::Decode(e.to_string()))?;
+        return Ok((
+            blob.samples,
+            SfxMetadata {
+                channels: blob.channels,
+                sample_rate: blob.sample_rate,
+                loop_points: blob.loop_points,
+            },
+        ));
+    }
+
     // Header: [u16 channels][u16 reserved][u32 sample_rate]  => 8 bytes
     // Optional: [u64 loop_start][u64 loop_end] => additional 16 bytes (total 24)
     if data.len() >= 8 {
@@ -159,24 +185,22 @@ pub fn resample_interleaved(
         oversampling_factor: 32,
         window: WindowFunction::BlackmanHarris2,
     };
-    let chunk_size = frames.max(1024);
+    // The whole clip is one chunk, padded with a sinc length of silence so
+    // the filter tail is flushed; the output is trimmed to the exact length
+    // below.
+    let chunk_size = frames + params.sinc_len;
 
     // build planar vectors
-    let mut planar: Vec<Vec<f32>> = vec![Vec::with_capacity(frames); channels];
+    let mut planar: Vec<Vec<f32>> = vec![vec![0.0; chunk_size]; channels];
     for f in 0..frames {
         for ch in 0..channels {
-            planar[ch].push(samples[f * channels + ch]);
+            planar[ch][f] = samples[f * channels + ch];
         }
     }
 
-    // SincFixedIn::new expected signature includes an extra f64 parameter (filter cutoff scaling)
-    // and expects channels before chunk_size in this rubato version.
-    let cutoff_scale: f64 = 0.95;
-    let max_ratio = if ratio < 1.0 { 1.0 } else { ratio };
-    // rubato's SincFixedIn in this version expects chunk_size before channels.
-    let mut resampler =
-        SincFixedIn::<f32>::new(cutoff_scale, max_ratio, params, chunk_size, channels)
-            .expect("failed to create rubato resampler");
+    // SincFixedIn::new(resample_ratio, max_resample_ratio_relative, params, chunk_size, channels)
+    let mut resampler = SincFixedIn::<f32>::new(ratio, 1.0, params, chunk_size, channels)
+        .expect("failed to create rubato resampler");
 
     // rubato expects slices: &[&[f32]] per chunk
     let input_refs: Vec<&[f32]> = planar.iter().map(|v| v.as_slice()).collect();
@@ -188,7 +212,7 @@ pub fn resample_interleaved(
     if outputs.is_empty() {
         return Vec::new();
     }
-    let out_frames = outputs[0].len();
+    let out_frames = ((frames as f64 * ratio).round() as usize).min(outputs[0].len());
     let mut out = vec![0.0f32; out_frames * channels];
     for f in 0..out_frames {
         for ch in 0..channels {
@@ -106,3 +106,60 @@ fn pkg_roundtrip() {
         assert_eq!(b, &a1[..]);
     }
 }
+
+#[test]
+fn mapped_slice_matches_read_backend() {
+    let dir = tempfile::tempdir().unwrap();
+    let pkg_path = dir.path().join("assets.pkg");
+    let payload: Vec<u8> = (0..64 * 1024u32).map(|i| (i * 31 % 251) as u8).collect();
+    let config = config::standard();
+
+    // Same layout as `pkg_roundtrip`: header, payload, index. The header's
+    // encoded length depends on the index offset, so settle it first.
+    let mut header_len = 0u64;
+    let (hdr_bytes, index_bytes) = loop {
+        let entries = vec![AssetIndexEntry {
+            name: "big.sfx".to_string(),
+            asset_type: AssetType::Sfx,
+            offset: header_len,
+            size: payload.len() as u64,
+            sample_rate: 48000,
+            channels: 1,
+            flags: 0,
+            checksum: None,
+        }];
+        let index_bytes = encode_to_vec(&entries, config).unwrap();
+        let hdr = PkgHeader::new(header_len + payload.len() as u64, &index_bytes, 0);
+        let hdr_bytes = encode_to_vec(&hdr, config).unwrap();
+        if hdr_bytes.len() as u64 == header_len {
+            break (hdr_bytes, index_bytes);
+        }
+        header_len = hdr_bytes.len() as u64;
+    };
+    let mut f = std::fs::File::create(&pkg_path).unwrap();
+    f.write_all(&hdr_bytes).unwrap();
+    f.write_all(&payload).unwrap();
+    f.write_all(&index_bytes).unwrap();
+    drop(f);
+
+    let mapped = AssetPkg::open(&pkg_path).expect("open mapped");
+    let read = AssetPkg::open_read(&pkg_path).expect("open read");
+    assert!(mapped.is_mapped());
+    assert!(!read.is_mapped());
+
+    match mapped.read_asset_bytes_cow("big.sfx").expect("mapped read") {
+        std::borrow::Cow::Borrowed(slice) => {
+            assert_eq!(slice, &read.read_asset_bytes("big.sfx").unwrap()[..]);
+            assert_eq!(slice, &payload[..]);
+        }
+        std::borrow::Cow::Owned(_) => panic!("mapped package should hand out borrowed slices"),
+    }
+    assert!(matches!(
+        read.read_asset_bytes_cow("big.sfx").expect("read-backed read"),
+        std::borrow::Cow::Owned(ref v) if v[..] == payload[..]
+    ));
+    assert!(matches!(
+        mapped.read_asset_bytes_cow("missing.sfx"),
+        Err(asset_manager::AssetError::NotFound)
+    ));
+}
@@ -67,3 +67,46 @@ fn sfx_zero_frames_rejected() {
         other => panic!("expected resource limit error, got {:?}", other),
     }
 }
+
+fn make_sfx2_bytes(channels: u8, frames: u64, loop_points: (u64, u64), samples: &[f32]) -> Vec<u8> {
+    let mut b = Vec::new();
+    b.extend_from_slice(b"SFX2");
+    b.push(0); // f32
+    b.push(channels);
+    b.push(asset_manager::sfx::SFX2_FLAG_LOOP);
+    b.push(0);
+    b.extend_from_slice(&48000u32.to_le_bytes());
+    b.extend_from_slice(&frames.to_le_bytes());
+    b.extend_from_slice(&loop_points.0.to_le_bytes());
+    b.extend_from_slice(&loop_points.1.to_le_bytes());
+    for s in samples {
+        b.extend_from_slice(&s.to_le_bytes());
+    }
+    b
+}
+
+#[test]
+fn sfx2_loop_region_round_trips() {
+    let samples = [0.1f32, 0.2, 0.3, 0.4];
+    let bytes = make_sfx2_bytes(1, 4, (1, 3), &samples);
+    let blob = SfxBlob::from_sfx_bytes(&bytes).expect("parse should succeed");
+    assert_eq!(blob.loop_points, Some((1, 3)));
+    assert_eq!(blob.samples, samples);
+}
+
+#[test]
+fn sfx1_still_loads_without_loop() {
+    let bytes = make_sfx_bytes(0, 1, 48000, 2, &[0.5f32, -0.5]);
+    let blob = SfxBlob::from_sfx_bytes(&bytes).expect("parse should succeed");
+    assert_eq!(blob.loop_points, None);
+    assert_eq!(blob.samples, vec![0.5, -0.5]);
+}
+
+#[test]
+fn sfx2_loop_past_end_rejected() {
+    let bytes = make_sfx2_bytes(1, 4, (2, 5), &[0.0f32; 4]);
+    match SfxBlob::from_sfx_bytes(&bytes) {
+        Err(AssetError::Decode(_)) => {}
+        other => panic!("expected decode error, got {:?}", other),
+    }
+}
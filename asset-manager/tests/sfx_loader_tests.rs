@@ -37,3 +37,20 @@ fn fallback_raw_interleaved() {
     // parse_pcm_sfx_data should error (no header)
     assert!(parse_pcm_sfx_data(&data).is_err());
 }
+
+#[test]
+fn parse_packed_sfx1_i16() {
+    // SFX1 header: magic, format=1 (i16), channels=1, reserved(2), sample_rate, frames
+    let mut data = Vec::new();
+    data.extend_from_slice(b"SFX1");
+    data.extend_from_slice(&[1u8, 1, 0, 0]);
+    data.extend_from_slice(&44100u32.to_le_bytes());
+    data.extend_from_slice(&3u64.to_le_bytes());
+    for s in [i16::MAX, 0, -i16::MAX] {
+        data.extend_from_slice(&s.to_le_bytes());
+    }
+    let (samples, meta) = parse_pcm_sfx_data(&data).expect("parse ok");
+    assert_eq!(meta.channels, 1);
+    assert_eq!(meta.sample_rate, 44100);
+    assert_eq!(samples, vec![1.0, 0.0, -1.0]);
+}
@@ -32,3 +32,32 @@ fn explicit_target_rate_returns_requested_rate() {
     assert_eq!(meta2.sample_rate, 48000);
     assert!(out2.len() > samples.len(), "out2.len={} samples.len={} meta2={:?}", out2.len(), samples.len(), meta2);
 }
+
+#[test]
+fn sfx2_loop_points_are_rescaled_to_target_rate() {
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("loop.sfx");
+    let frames = 441u64;
+    let mut data = Vec::new();
+    data.extend_from_slice(b"SFX2");
+    data.push(0); // f32
+    data.push(1); // mono
+    data.push(asset_manager::sfx::SFX2_FLAG_LOOP);
+    data.push(0);
+    data.extend_from_slice(&44100u32.to_le_bytes());
+    data.extend_from_slice(&frames.to_le_bytes());
+    data.extend_from_slice(&100u64.to_le_bytes());
+    data.extend_from_slice(&441u64.to_le_bytes());
+    for i in 0..frames {
+        data.extend_from_slice(&((i as f32 * 0.05).sin() * 0.5).to_le_bytes());
+    }
+    File::create(&p).unwrap().write_all(&data).unwrap();
+
+    let (out, meta) = asset_manager::sfx_loader::load_sfx_path_with_target(&p, 48000).unwrap();
+    assert_eq!(meta.sample_rate, 48000);
+    let (start, end) = meta.loop_points.expect("loop region preserved");
+    assert_eq!(start, 109, "100 frames at 44.1 kHz is ~108.8 at 48 kHz");
+    assert!(end <= out.len() as u64, "loop end must stay inside the resampled data");
+    assert_eq!(out.len(), 480, "441 frames at 44.1 kHz resample to 480 at 48 kHz");
+    assert_eq!(end, 480, "a loop ending at the last frame must still end at the last frame");
+}
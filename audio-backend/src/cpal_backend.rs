@@ -2,11 +2,11 @@ use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
 use std::thread::{self, JoinHandle};
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, StreamConfig, SampleFormat};
+use cpal::{Device, FromSample, SampleFormat, SampleRate, SizedSample, StreamConfig, SupportedBufferSize, SupportedStreamConfig};
 use crossbeam_channel::{unbounded, bounded, Sender, Receiver};
 use arc_swap::ArcSwapOption;
 
-use crate::{BackendError, RenderFn, DeviceInfo, AudioBackend, DiagnosticEvent, DiagnosticsCb, DeviceInfoProvider};
+use crate::{BackendError, RenderFn, DeviceInfo, AudioBackend, DiagnosticEvent, DiagnosticsCb, DeviceInfoProvider, DeviceSummary};
 
 /// Worker-thread-backed CPAL backend.
 /// Public `CpalAudioBackend` is a Send-safe handle that communicates with the
@@ -49,6 +49,13 @@ struct CpalBackendInner {
     frames: AtomicU64,
     // Diagnostics callback (worker uses this via a clone of the Arc)
     diagnostics: Arc<ArcSwapOption<DiagnosticsCb>>,
+    // Latest output latency estimate in nanoseconds; 0 = not running yet.
+    latency_ns: AtomicU64,
+    // Cumulative stream errors (xruns) reported by the host since creation.
+    xruns: AtomicU64,
+    // Effective buffer size when a requested one couldn't be honored; reported
+    // on start because no diagnostics callback exists at construction time.
+    buffer_size_fallback: Option<usize>,
 }
 
 enum CtrlMsg {
@@ -58,42 +65,83 @@ enum CtrlMsg {
     // that rendering has fully stopped (no further data callbacks will occur).
     StopAck(Sender<()>),
     SetDiagnostics(Option<DiagnosticsCb>),
+    // Pause/Resume act on the retained stream and reply with the outcome.
+    Pause(Sender<Result<(), BackendError>>),
+    Resume(Sender<Result<(), BackendError>>),
 }
 
 impl CpalAudioBackend {
     pub fn new() -> Result<Self, BackendError> {
         let host = cpal::default_host();
         let device = host.default_output_device().ok_or(BackendError::DeviceNotFound)?;
+        Self::from_device(device)
+    }
 
-            // Prefer the device's default output config when available. This matches
-            // how simple examples (like `play_tone`) pick the OS-configured default
-            // and avoids surprising sample-rate differences.
-            let config = match device.default_output_config() {
-                Ok(default_cfg) => {
-                    eprintln!("Using device.default_output_config(): {:?}", default_cfg);
-                    default_cfg.config()
-                }
-                Err(_) => {
-                    // Fallback: enumerate supported configs and pick an f32 stereo one.
-                    let mut supported_configs = device.supported_output_configs()
-                        .map_err(|e| BackendError::Other(e.to_string()))?
-                        .collect::<Vec<_>>();
-
-                    if supported_configs.is_empty() {
-                        return Err(BackendError::UnsupportedFormat("no supported configs".into()));
-                    }
+    /// Open the output device whose name matches `name` exactly (as reported
+    /// by `list_output_devices`).
+    pub fn with_device_name(name: &str) -> Result<Self, BackendError> {
+        let host = cpal::default_host();
+        let mut devices = host.output_devices()
+            .map_err(|e| BackendError::Other(e.to_string()))?;
+        let device = devices
+            .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+            .ok_or(BackendError::DeviceNotFound)?;
+        Self::from_device(device)
+    }
+
+    /// Open the default output device, asking for a specific sample rate
+    /// and/or fixed buffer size. Requests the device can't satisfy fall back
+    /// to the device default (rate) or the nearest supported size (buffer);
+    /// the effective values are reported by `sample_rate()`/`buffer_size()`.
+    /// An unhonored buffer request is also reported as
+    /// `DiagnosticEvent::BufferSizeChanged` once the stream starts.
+    pub fn with_config(sample_rate: Option<u32>, buffer_frames: Option<u32>) -> Result<Self, BackendError> {
+        let host = cpal::default_host();
+        let device = host.default_output_device().ok_or(BackendError::DeviceNotFound)?;
+        Self::open(device, sample_rate, buffer_frames)
+    }
+
+    fn from_device(device: Device) -> Result<Self, BackendError> {
+        Self::open(device, None, None)
+    }
 
-                    // Prefer f32 interleaved, stereo, maximum sample rate.
-                    let chosen = supported_configs.iter()
-                        .rev()
-                        .find(|c| c.sample_format() == SampleFormat::F32 && c.channels() >= 2)
-                        .cloned()
-                        .or_else(|| supported_configs.pop())
-                        .unwrap();
+    fn open(device: Device, sample_rate: Option<u32>, buffer_frames: Option<u32>) -> Result<Self, BackendError> {
+        let supported = select_output_config(&device, sample_rate, None)?;
+        Self::open_with(device, supported, buffer_frames)
+    }
+
+    /// Open the default output device using a config of the given sample
+    /// format (e.g. to force an integer stream on a device whose default is
+    /// f32). Returns `UnsupportedFormat` if the device offers no such config.
+    pub fn with_sample_format(format: SampleFormat) -> Result<Self, BackendError> {
+        let host = cpal::default_host();
+        let device = host.default_output_device().ok_or(BackendError::DeviceNotFound)?;
+        let supported = select_output_config(&device, None, Some(format))?;
+        Self::open_with(device, supported, None)
+    }
+
+    fn open_with(device: Device, supported: SupportedStreamConfig, buffer_frames: Option<u32>) -> Result<Self, BackendError> {
+        let sample_format = supported.sample_format();
+        let mut config = supported.config();
 
-                    chosen.with_max_sample_rate().config()
+        // `Some(frames)` when a buffer size was requested but not honored.
+        let mut buffer_size_fallback = None;
+        if let Some(requested) = buffer_frames {
+            match supported.buffer_size() {
+                SupportedBufferSize::Range { min, max } => {
+                    let frames = requested.clamp(*min, *max);
+                    config.buffer_size = cpal::BufferSize::Fixed(frames);
+                    if frames != requested {
+                        eprintln!("requested buffer size {} outside supported range {}..={}; using {}", requested, min, max, frames);
+                        buffer_size_fallback = Some(frames as usize);
+                    }
+                }
+                SupportedBufferSize::Unknown => {
+                    eprintln!("device does not report buffer size limits; ignoring requested size {}", requested);
+                    buffer_size_fallback = Some(0);
                 }
-            };
+            }
+        }
 
         let buffer_frames = match config.buffer_size {
             cpal::BufferSize::Fixed(n) => n as usize,
@@ -114,12 +162,15 @@ impl CpalAudioBackend {
             render: Arc::new(ArcSwapOption::from(None)),
             frames: AtomicU64::new(0),
             diagnostics: Arc::new(ArcSwapOption::from(None)),
+            latency_ns: AtomicU64::new(0),
+            xruns: AtomicU64::new(0),
+            buffer_size_fallback,
         });
 
         // Spawn worker thread that owns the device, stream, and conversion buffers.
         let inner_worker = inner.clone();
         let handle = thread::spawn(move || {
-            worker_loop(device, config, rx, inner_worker);
+            worker_loop(device, config, sample_format, rx, inner_worker);
         });
 
         Ok(Self { 
@@ -130,6 +181,130 @@ impl CpalAudioBackend {
     }
 }
 
+impl CpalAudioBackend {
+    // Send a control message that carries a reply channel and wait for the
+    // worker's answer.
+    fn request(&self, msg: impl FnOnce(Sender<Result<(), BackendError>>) -> CtrlMsg) -> Result<(), BackendError> {
+        let tx = self.ctrl_tx.as_ref().ok_or_else(|| BackendError::Other("ctrl channel closed".into()))?;
+        let (reply_tx, reply_rx) = bounded(1);
+        tx.send(msg(reply_tx)).map_err(|_| BackendError::Other("ctrl channel closed".into()))?;
+        reply_rx
+            .recv_timeout(std::time::Duration::from_millis(500))
+            .map_err(|_| BackendError::Other("worker did not reply".into()))?
+    }
+}
+
+/// Pick the output config for `device`. With a requested sample format, only
+/// configs of that format are considered (preferring the default rate and
+/// channel count). With a requested sample rate, prefer an f32 config covering
+/// that rate (matching the default channel count where possible); otherwise,
+/// or if no config covers it, use the device default.
+fn select_output_config(device: &Device, sample_rate: Option<u32>, sample_format: Option<SampleFormat>) -> Result<SupportedStreamConfig, BackendError> {
+    let default_cfg = device.default_output_config().ok();
+
+    if let Some(format) = sample_format {
+        if let Some(cfg) = default_cfg.as_ref().filter(|c| c.sample_format() == format) {
+            return Ok(cfg.clone());
+        }
+        let configs = device.supported_output_configs()
+            .map_err(|e| BackendError::Other(e.to_string()))?;
+        let default_rate = default_cfg.as_ref().map(|c| c.sample_rate());
+        let default_channels = default_cfg.as_ref().map(|c| c.channels());
+        let chosen = configs
+            .filter(|c| c.sample_format() == format)
+            .max_by_key(|c| Some(c.channels()) == default_channels)
+            .ok_or_else(|| BackendError::UnsupportedFormat(format!("device has no {:?} output config", format)))?;
+        return Ok(match default_rate {
+            Some(sr) if chosen.min_sample_rate() <= sr && sr <= chosen.max_sample_rate() => chosen.with_sample_rate(sr),
+            _ => chosen.with_max_sample_rate(),
+        });
+    }
+
+    if let Some(sr) = sample_rate {
+        if let Some(cfg) = default_cfg.as_ref().filter(|c| c.sample_rate().0 == sr) {
+            return Ok(cfg.clone());
+        }
+        let default_channels = default_cfg.as_ref().map(|c| c.channels());
+        if let Ok(configs) = device.supported_output_configs() {
+            let chosen = configs
+                .filter(|c| c.min_sample_rate().0 <= sr && sr <= c.max_sample_rate().0)
+                .max_by_key(|c| (c.sample_format() == SampleFormat::F32, Some(c.channels()) == default_channels));
+            if let Some(c) = chosen {
+                return Ok(c.with_sample_rate(SampleRate(sr)));
+            }
+        }
+        eprintln!("requested sample rate {} not supported by device; using default", sr);
+    }
+
+    // Prefer the device's default output config when available. This matches
+    // how simple examples (like `play_tone`) pick the OS-configured default
+    // and avoids surprising sample-rate differences.
+    if let Some(cfg) = default_cfg {
+        eprintln!("Using device.default_output_config(): {:?}", cfg);
+        return Ok(cfg);
+    }
+
+    // Fallback: enumerate supported configs and pick an f32 stereo one.
+    let mut supported_configs = device.supported_output_configs()
+        .map_err(|e| BackendError::Other(e.to_string()))?
+        .collect::<Vec<_>>();
+
+    if supported_configs.is_empty() {
+        return Err(BackendError::UnsupportedFormat("no supported configs".into()));
+    }
+
+    // Prefer f32 interleaved, stereo, maximum sample rate.
+    let chosen = supported_configs.iter()
+        .rev()
+        .find(|c| c.sample_format() == SampleFormat::F32 && c.channels() >= 2)
+        .cloned()
+        .or_else(|| supported_configs.pop())
+        .unwrap();
+
+    Ok(chosen.with_max_sample_rate())
+}
+
+/// Enumerate the default host's output devices and their supported configs.
+/// Devices whose name cannot be queried are skipped.
+pub fn list_output_devices() -> Vec<DeviceSummary> {
+    let host = cpal::default_host();
+    let default_name = host.default_output_device().and_then(|d| d.name().ok());
+    let devices = match host.output_devices() {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("list_output_devices: failed to enumerate devices: {}", e);
+            return Vec::new();
+        }
+    };
+
+    devices
+        .filter_map(|device| {
+            let name = device.name().ok()?;
+            let mut sample_rates: Vec<(u32, u32)> = Vec::new();
+            let mut channels: Vec<u16> = Vec::new();
+            if let Ok(configs) = device.supported_output_configs() {
+                for c in configs {
+                    let range = (c.min_sample_rate().0, c.max_sample_rate().0);
+                    if !sample_rates.contains(&range) {
+                        sample_rates.push(range);
+                    }
+                    if !channels.contains(&c.channels()) {
+                        channels.push(c.channels());
+                    }
+                }
+            }
+            sample_rates.sort_unstable();
+            channels.sort_unstable();
+            Some(DeviceSummary {
+                is_default: default_name.as_deref() == Some(name.as_str()),
+                name,
+                sample_rates,
+                channels,
+            })
+        })
+        .collect()
+}
+
 // Implements the Drop trait to ensure the worker thread is gracefully shut down.
 // This is crucial for preventing the race condition and the access violation.
 impl Drop for CpalAudioBackend {
@@ -145,70 +320,157 @@ impl Drop for CpalAudioBackend {
     }
 }
 
-fn worker_loop(device: Device, config: StreamConfig, rx: Receiver<CtrlMsg>, inner: Arc<CpalBackendInner>) {
-    let mut _conv_buf: Vec<f32> = Vec::new();
+/// Run the render callback into `data` (f32, interleaved) and update the frame
+/// counter and latency estimate. Shared by the native-f32 and converting
+/// stream callbacks.
+fn render_block(inner: &CpalBackendInner, data: &mut [f32], channels: usize, sample_rate: u32, info: &cpal::OutputCallbackInfo) {
+    let opt_render = inner.render.load();
+    if let Some(render) = opt_render.as_ref() {
+        let frames = data.len() / channels;
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            (**render)(data, sample_rate, frames);
+        }));
+        if res.is_err() {
+            data.iter_mut().for_each(|s| *s = 0.0);
+        }
+    } else {
+        data.iter_mut().for_each(|s| *s = 0.0);
+    }
+
+    let frames_written = (data.len() / channels) as u64;
+    inner.frames.fetch_add(frames_written, Ordering::Relaxed);
+
+    // Prefer the host's callback->playback timestamps; fall back to
+    // one buffer's worth of audio when they aren't meaningful.
+    let ts = info.timestamp();
+    let latency = ts.playback.duration_since(&ts.callback)
+        .filter(|d| !d.is_zero())
+        .unwrap_or_else(|| std::time::Duration::from_secs_f64(frames_written as f64 / sample_rate as f64));
+    inner.latency_ns.store(latency.as_nanos() as u64, Ordering::Relaxed);
+}
+
+/// Build a stream for an integer device format. The render callback still
+/// produces f32; it renders into a scratch buffer owned by the data callback
+/// and the result is clamped and converted to `T`.
+fn build_converted_stream<T, E>(device: &Device, config: &StreamConfig, inner: Arc<CpalBackendInner>, err_cb: E) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample + FromSample<f32>,
+    E: FnMut(cpal::StreamError) + Send + 'static,
+{
+    let channels = config.channels as usize;
+    let sample_rate = config.sample_rate.0;
+    // Sized for the requested buffer (or a generous default) so the callback
+    // normally never allocates.
+    let frames_hint = match config.buffer_size {
+        cpal::BufferSize::Fixed(n) => n as usize,
+        cpal::BufferSize::Default => 4096,
+    };
+    let mut scratch = vec![0.0f32; frames_hint * channels];
+
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], info: &cpal::OutputCallbackInfo| {
+            if scratch.len() < data.len() {
+                // Host handed us a larger buffer than advertised; grow once.
+                scratch.resize(data.len(), 0.0);
+            }
+            let buf = &mut scratch[..data.len()];
+            render_block(&inner, buf, channels, sample_rate, info);
+            for (out, &s) in data.iter_mut().zip(buf.iter()) {
+                *out = T::from_sample(s.clamp(-1.0, 1.0));
+            }
+        },
+        err_cb,
+        None,
+    )
+}
+
+// Invoke the diagnostics callback, if any. Worker thread only (never RT).
+fn emit_diagnostic(inner: &CpalBackendInner, event: DiagnosticEvent) {
+    if let Some(cb) = &*inner.diagnostics.load() {
+        cb(event);
+    }
+}
+
+fn worker_loop(device: Device, config: StreamConfig, sample_format: SampleFormat, rx: Receiver<CtrlMsg>, inner: Arc<CpalBackendInner>) {
     let channels = config.channels as usize;
 
     let mut stream_opt: Option<cpal::Stream> = None;
 
+    // Stream error callbacks only count the xrun and queue the event here; the
+    // worker drains the queue and invokes the diagnostics callback itself, so
+    // no thread is spawned per error.
+    let (diag_tx, diag_rx) = unbounded::<DiagnosticEvent>();
+
     loop {
-        // We block on `rx.recv()` here, which is fine because this is a dedicated worker thread.
-        // `recv()` will return an error if the channel is disconnected (i.e., `CpalAudioBackend` is dropped and all senders are gone).
-        match rx.recv() {
+        // We block here, which is fine because this is a dedicated worker thread.
+        // `rx` returns an error once it is disconnected (i.e., `CpalAudioBackend` is dropped and all senders are gone).
+        let msg = crossbeam_channel::select! {
+            recv(rx) -> msg => msg,
+            recv(diag_rx) -> ev => {
+                if let Ok(ev) = ev {
+                    emit_diagnostic(&inner, ev);
+                }
+                continue;
+            }
+        };
+        match msg {
             Ok(msg) => {
                 match msg {
                     CtrlMsg::SetRender(opt) => {
                         inner.render.store(opt.map(Arc::new));
                     }
                     CtrlMsg::Start => {
-                        if stream_opt.is_none() {
+                        if let Some(s) = stream_opt.as_ref() {
+                            // A paused stream is still around; restart it rather than rebuilding.
+                            if let Err(e) = s.play() {
+                                eprintln!("Failed to play stream: {}", e);
+                            }
+                        } else {
                             // Clone `inner` for the error callback.
                             let inner_for_err_cb = inner.clone();
+                            let diag_tx = diag_tx.clone();
                             let err_cb = move |err| {
                                 eprintln!("CPAL stream error: {}", err);
-                                if let Some(cb) = &*inner_for_err_cb.diagnostics.load() {
-                                    let cb_clone = cb.clone();
-                                    std::thread::spawn(move || cb_clone(DiagnosticEvent::XRun { count: 1 }));
-                                }
+                                inner_for_err_cb.xruns.fetch_add(1, Ordering::Relaxed);
+                                let _ = diag_tx.send(DiagnosticEvent::XRun { count: 1 });
                             };
 
-                            // Clone `inner` again for the data callback.
-                            let inner_for_data_cb = inner.clone();
-                            let channels_local = channels;
-                            let sample_rate = config.sample_rate.0;
-
-                            let data_cb = move |data: &mut [f32], _info: &cpal::OutputCallbackInfo| {
-                                let opt_render = inner_for_data_cb.render.load();
-                                if let Some(render) = opt_render.as_ref() {
-                                    let frames = data.len() / channels_local;
-                                    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                                        (**render)(data, sample_rate, frames);
-                                    }));
-                                    if res.is_err() {
-                                        data.iter_mut().for_each(|s| *s = 0.0);
-                                    }
-                                } else {
-                                    data.iter_mut().for_each(|s| *s = 0.0);
+                            let stream_res = match sample_format {
+                                SampleFormat::F32 => {
+                                    let inner_for_data_cb = inner.clone();
+                                    let sample_rate = config.sample_rate.0;
+                                    device.build_output_stream(
+                                        &config,
+                                        move |data: &mut [f32], info: &cpal::OutputCallbackInfo| {
+                                            render_block(&inner_for_data_cb, data, channels, sample_rate, info);
+                                        },
+                                        err_cb,
+                                        None,
+                                    )
+                                }
+                                SampleFormat::I16 => build_converted_stream::<i16, _>(&device, &config, inner.clone(), err_cb),
+                                SampleFormat::U16 => build_converted_stream::<u16, _>(&device, &config, inner.clone(), err_cb),
+                                other => {
+                                    eprintln!("Unsupported output sample format: {:?}", other);
+                                    Err(cpal::BuildStreamError::StreamConfigNotSupported)
                                 }
-
-                                let frames_written = (data.len() / channels_local) as u64;
-                                inner_for_data_cb.frames.fetch_add(frames_written, Ordering::Relaxed);
                             };
 
-                            match device.build_output_stream(&config, data_cb, err_cb, None) {
+                            match stream_res {
                                 Ok(s) => {
                                     if let Err(e) = s.play() {
                                         eprintln!("Failed to play stream: {}", e);
                                     } else {
                                         stream_opt = Some(s);
+                                        if let Some(frames) = inner.buffer_size_fallback {
+                                            emit_diagnostic(&inner, DiagnosticEvent::BufferSizeChanged { frames });
+                                        }
                                     }
                                 }
                                 Err(e) => {
                                     eprintln!("Failed to build stream: {}", e);
-                                    if let Some(cb) = &*inner.diagnostics.load() {
-                                        let cb_clone = cb.clone();
-                                        std::thread::spawn(move || cb_clone(DiagnosticEvent::Other(format!("stream build failed: {}", e))));
-                                    }
+                                    emit_diagnostic(&inner, DiagnosticEvent::Other(format!("stream build failed: {}", e)));
                                 }
                             }
                         }
@@ -216,6 +478,7 @@ fn worker_loop(device: Device, config: StreamConfig, rx: Receiver<CtrlMsg>, inne
                     CtrlMsg::StopAck(ack) => {
                         // Drop the stream to stop callbacks.
                         stream_opt = None;
+                        inner.latency_ns.store(0, Ordering::Relaxed);
 
                         // Wait until frames counter stabilizes (no further frames written)
                         // so callers can deterministically know no more callbacks will occur.
@@ -244,6 +507,20 @@ fn worker_loop(device: Device, config: StreamConfig, rx: Receiver<CtrlMsg>, inne
                     CtrlMsg::SetDiagnostics(cb) => {
                         inner.diagnostics.store(cb.map(Arc::new));
                     }
+                    CtrlMsg::Pause(reply) => {
+                        let res = match stream_opt.as_ref() {
+                            Some(s) => s.pause().map_err(|e| BackendError::PlaybackError(e.to_string())),
+                            None => Err(BackendError::Other("stream not started".into())),
+                        };
+                        let _ = reply.send(res);
+                    }
+                    CtrlMsg::Resume(reply) => {
+                        let res = match stream_opt.as_ref() {
+                            Some(s) => s.play().map_err(|e| BackendError::PlaybackError(e.to_string())),
+                            None => Err(BackendError::Other("stream not started".into())),
+                        };
+                        let _ = reply.send(res);
+                    }
                     // Exit the loop and the thread.
                     // No explicit Shutdown message; worker will exit when channel disconnects.
                 }
@@ -279,10 +556,25 @@ impl AudioBackend for CpalAudioBackend {
     Ok(())
     }
 
+    fn pause(&mut self) -> Result<(), BackendError> {
+        self.request(CtrlMsg::Pause)
+    }
+
+    fn resume(&mut self) -> Result<(), BackendError> {
+        self.request(CtrlMsg::Resume)
+    }
+
     fn sample_rate(&self) -> u32 { self.inner.info.sample_rate }
     fn buffer_size(&self) -> usize { self.inner.info.buffer_size }
     fn channels(&self) -> u16 { self.inner.info.channels }
     fn frames_since_start(&self) -> u64 { self.inner.frames.load(Ordering::Relaxed) }
+    fn xrun_count(&self) -> u64 { self.inner.xruns.load(Ordering::Relaxed) }
+    fn output_latency(&self) -> Option<std::time::Duration> {
+        match self.inner.latency_ns.load(Ordering::Relaxed) {
+            0 => None,
+            ns => Some(std::time::Duration::from_nanos(ns)),
+        }
+    }
     fn set_diagnostics_callback(&mut self, cb: Option<DiagnosticsCb>) {
         if let Some(tx) = self.ctrl_tx.as_ref() {
             tx.send(CtrlMsg::SetDiagnostics(cb)).ok();
@@ -12,6 +12,10 @@ pub mod mock_backend;
 #[cfg(not(feature = "mock-audio"))]
 pub mod cpal_backend;
 
+// Offline output: streamed WAV files with sizes patched on finalize.
+pub mod wav_writer;
+pub use wav_writer::{WavSampleFormat, WavWriter};
+
 
 /// A specialized error type for audio backend failures.
 #[derive(Debug)]
@@ -60,6 +64,19 @@ pub struct DeviceInfo {
     pub device_name: Option<String>,
 }
 
+/// Summary of an output device, suitable for presenting a device picker
+/// without depending on the underlying host API directly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceSummary {
+    pub name: String,
+    /// True for the host's default output device.
+    pub is_default: bool,
+    /// Supported sample-rate ranges as inclusive `(min, max)` pairs.
+    pub sample_rates: Vec<(u32, u32)>,
+    /// Supported channel counts, ascending.
+    pub channels: Vec<u16>,
+}
+
 /// A trait for backends that can provide additional information about the audio device.
 pub trait DeviceInfoProvider {
     fn get_device_name(&self) -> Option<&str>;
@@ -71,11 +88,25 @@ pub trait DeviceInfoProvider {
 pub trait AudioBackend {
     fn start(&mut self, render: RenderFn) -> Result<(), BackendError>;
     fn stop(&mut self) -> Result<(), BackendError>;
+    /// Suspend rendering while keeping the stream (and render callback)
+    /// alive, so `resume()` restarts output without rebuilding it. Errors if
+    /// the backend hasn't been started.
+    fn pause(&mut self) -> Result<(), BackendError>;
+    /// Resume a stream suspended by `pause()`.
+    fn resume(&mut self) -> Result<(), BackendError>;
     fn sample_rate(&self) -> u32;
     fn buffer_size(&self) -> usize;
     fn channels(&self) -> u16;
     /// Returns frames since stream start. 0 if not running.
     fn frames_since_start(&self) -> u64;
+    /// Estimated delay between rendering a frame and it reaching the output.
+    /// `None` until the stream has started (and after `stop()`).
+    fn output_latency(&self) -> Option<std::time::Duration>;
+    /// Total xruns (stream errors) seen since the backend was created, for
+    /// monitoring UIs that poll rather than subscribe to diagnostics.
+    fn xrun_count(&self) -> u64 {
+        0
+    }
     /// Register or clear non-RT diagnostics callback.
     fn set_diagnostics_callback(&mut self, cb: Option<DiagnosticsCb>);
     
@@ -93,6 +124,72 @@ pub fn create_audio_backend() -> Result<Box<dyn AudioBackend>, BackendError> {
     Ok(Box::new(backend))
 }
 
+/// Like `create_audio_backend`, but opens the output device named `name`
+/// instead of the default one. Returns `BackendError::DeviceNotFound` if no
+/// device reports that name.
+#[cfg(not(feature = "mock-audio"))]
+pub fn create_audio_backend_for_device(name: &str) -> Result<Box<dyn AudioBackend>, BackendError> {
+    let backend = cpal_backend::CpalAudioBackend::with_device_name(name)?;
+    eprintln!("create_audio_backend_for_device: using CPAL backend -> sample_rate={} buffer_size={} channels={} device_name={}",
+        backend.sample_rate(), backend.buffer_size(), backend.channels(), name
+    );
+    Ok(Box::new(backend))
+}
+
+/// Like `create_audio_backend`, but opens the output device named `name`
+/// instead of the default one. Returns `BackendError::DeviceNotFound` if no
+/// device reports that name.
+#[cfg(feature = "mock-audio")]
+pub fn create_audio_backend_for_device(name: &str) -> Result<Box<dyn AudioBackend>, BackendError> {
+    let backend = mock_backend::MockAudioBackend::with_device_name(name)?;
+    eprintln!("create_audio_backend_for_device: using MOCK backend -> sample_rate={} buffer_size={} channels={} device_name={}",
+        backend.sample_rate(), backend.buffer_size(), backend.channels(), name
+    );
+    Ok(Box::new(backend))
+}
+
+/// Like `create_audio_backend`, but requests a sample rate and/or fixed
+/// buffer size from the default device. Check `sample_rate()` and
+/// `buffer_size()` on the result for the values actually in effect.
+#[cfg(not(feature = "mock-audio"))]
+pub fn create_audio_backend_with_config(sample_rate: Option<u32>, buffer_frames: Option<u32>) -> Result<Box<dyn AudioBackend>, BackendError> {
+    let backend = cpal_backend::CpalAudioBackend::with_config(sample_rate, buffer_frames)?;
+    eprintln!("create_audio_backend_with_config: using CPAL backend -> sample_rate={} buffer_size={} channels={} (requested sample_rate={:?} buffer_frames={:?})",
+        backend.sample_rate(), backend.buffer_size(), backend.channels(), sample_rate, buffer_frames
+    );
+    Ok(Box::new(backend))
+}
+
+/// Like `create_audio_backend`, but requests a sample rate and/or fixed
+/// buffer size from the default device. Check `sample_rate()` and
+/// `buffer_size()` on the result for the values actually in effect.
+#[cfg(feature = "mock-audio")]
+pub fn create_audio_backend_with_config(sample_rate: Option<u32>, buffer_frames: Option<u32>) -> Result<Box<dyn AudioBackend>, BackendError> {
+    let backend = mock_backend::MockAudioBackend::with_config(sample_rate, buffer_frames)?;
+    eprintln!("create_audio_backend_with_config: using MOCK backend -> sample_rate={} buffer_size={} channels={} (requested sample_rate={:?} buffer_frames={:?})",
+        backend.sample_rate(), backend.buffer_size(), backend.channels(), sample_rate, buffer_frames
+    );
+    Ok(Box::new(backend))
+}
+
+/// Names of the output devices available to the compiled backend, in host
+/// order. Any of these can be passed to `create_audio_backend_for_device`.
+pub fn list_output_device_names() -> Vec<String> {
+    list_output_devices().into_iter().map(|d| d.name).collect()
+}
+
+/// Enumerate the output devices available to the compiled backend.
+#[cfg(not(feature = "mock-audio"))]
+pub fn list_output_devices() -> Vec<DeviceSummary> {
+    cpal_backend::list_output_devices()
+}
+
+/// Enumerate the output devices available to the compiled backend.
+#[cfg(feature = "mock-audio")]
+pub fn list_output_devices() -> Vec<DeviceSummary> {
+    mock_backend::list_output_devices()
+}
+
 /// Runtime helper to determine if the `mock-audio` feature was enabled at
 /// compile time for this crate. Call from dependent crates/tests to confirm
 /// which backend variant was compiled.
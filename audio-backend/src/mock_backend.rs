@@ -1,6 +1,6 @@
 use crate::DeviceInfoProvider;
-use crate::{AudioBackend, BackendError, DeviceInfo, DiagnosticsCb, RenderFn};
-use std::sync::atomic::{AtomicU64, Ordering};
+use crate::{AudioBackend, BackendError, DeviceInfo, DeviceSummary, DiagnosticsCb, RenderFn};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
 /// A Send-safe mock audio backend using arc-swap for RT-safe render access.
@@ -10,6 +10,8 @@ pub struct MockAudioBackend {
     render: Arc<Mutex<Option<crate::RenderFn>>>,
     frames: AtomicU64,
     diagnostics: Option<DiagnosticsCb>,
+    paused: AtomicBool,
+    xruns: AtomicU64,
 }
 
 impl MockAudioBackend {
@@ -24,10 +26,79 @@ impl MockAudioBackend {
             render: Arc::new(Mutex::new(None)),
             frames: AtomicU64::new(0),
             diagnostics: None,
+            paused: AtomicBool::new(false),
+            xruns: AtomicU64::new(0),
         }
     }
 }
 
+impl MockAudioBackend {
+    /// Mirror of `CpalAudioBackend::with_device_name`: only the single mock
+    /// device can be opened.
+    pub fn with_device_name(name: &str) -> Result<Self, BackendError> {
+        if name == "mock-device" {
+            Ok(Self::new())
+        } else {
+            Err(BackendError::DeviceNotFound)
+        }
+    }
+
+    /// Mirror of `CpalAudioBackend::with_config`. The mock honors any non-zero
+    /// request; `None` or 0 keeps the default (48000 Hz / 256 frames).
+    pub fn with_config(sample_rate: Option<u32>, buffer_frames: Option<u32>) -> Result<Self, BackendError> {
+        let mut backend = Self::new();
+        if let Some(sr) = sample_rate.filter(|&sr| sr > 0) {
+            backend.info.sample_rate = sr;
+        }
+        if let Some(frames) = buffer_frames.filter(|&n| n > 0) {
+            backend.info.buffer_size = frames as usize;
+        }
+        Ok(backend)
+    }
+}
+
+impl MockAudioBackend {
+    /// Simulate a host stream error: bumps `xrun_count()` and delivers
+    /// `DiagnosticEvent::XRun` to the diagnostics callback on the caller's thread.
+    pub fn inject_xrun(&self) {
+        self.xruns.fetch_add(1, Ordering::Relaxed);
+        if let Some(cb) = &self.diagnostics {
+            cb(crate::DiagnosticEvent::XRun { count: 1 });
+        }
+    }
+}
+
+impl MockAudioBackend {
+    /// Drive one device callback by hand: render `buffer_size` frames into
+    /// `out` (resized to fit) and advance `frames_since_start()`. Returns
+    /// false without calling the render fn or advancing while stopped or
+    /// paused, as a real device would deliver no callbacks.
+    pub fn render_block(&self, out: &mut Vec<f32>) -> bool {
+        if self.paused.load(Ordering::Relaxed) {
+            return false;
+        }
+        let Some(render) = self.render.lock().unwrap().clone() else {
+            return false;
+        };
+        let frames = self.info.buffer_size;
+        out.clear();
+        out.resize(frames * self.info.channels as usize, 0.0);
+        render(out, self.info.sample_rate, frames);
+        self.frames.fetch_add(frames as u64, Ordering::Relaxed);
+        true
+    }
+}
+
+/// The mock backend exposes a single default device matching `MockAudioBackend::new`.
+pub fn list_output_devices() -> Vec<DeviceSummary> {
+    vec![DeviceSummary {
+        name: "mock-device".to_string(),
+        is_default: true,
+        sample_rates: vec![(48000, 48000)],
+        channels: vec![2],
+    }]
+}
+
 impl Default for MockAudioBackend {
     fn default() -> Self {
         Self::new()
@@ -38,12 +109,30 @@ impl AudioBackend for MockAudioBackend {
     fn start(&mut self, render: RenderFn) -> Result<(), BackendError> {
         let mut g = self.render.lock().unwrap();
         *g = Some(render);
+        self.paused.store(false, Ordering::Relaxed);
         Ok(())
     }
 
     fn stop(&mut self) -> Result<(), BackendError> {
         let mut g = self.render.lock().unwrap();
         *g = None;
+        self.paused.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn pause(&mut self) -> Result<(), BackendError> {
+        if self.render.lock().unwrap().is_none() {
+            return Err(BackendError::Other("stream not started".into()));
+        }
+        self.paused.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn resume(&mut self) -> Result<(), BackendError> {
+        if self.render.lock().unwrap().is_none() {
+            return Err(BackendError::Other("stream not started".into()));
+        }
+        self.paused.store(false, Ordering::Relaxed);
         Ok(())
     }
 
@@ -59,6 +148,16 @@ impl AudioBackend for MockAudioBackend {
     fn frames_since_start(&self) -> u64 {
         self.frames.load(Ordering::Relaxed)
     }
+    fn xrun_count(&self) -> u64 {
+        self.xruns.load(Ordering::Relaxed)
+    }
+    /// Fixed estimate of one buffer's duration while a render is installed.
+    fn output_latency(&self) -> Option<std::time::Duration> {
+        self.render.lock().unwrap().as_ref()?;
+        Some(std::time::Duration::from_secs_f64(
+            self.info.buffer_size as f64 / self.info.sample_rate as f64,
+        ))
+    }
     fn set_diagnostics_callback(&mut self, cb: Option<DiagnosticsCb>) {
         self.diagnostics = cb;
     }
@@ -0,0 +1,206 @@
+//! Streaming WAV writer for offline / file renders.
+//!
+//! The total length of a streamed render isn't known until it ends, so the
+//! writer emits a header with zeroed sizes up front and seeks back to patch the
+//! RIFF, `fact` and `data` sizes in `finalize()`.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Sample encoding used for the `data` chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavSampleFormat {
+    /// 32-bit IEEE float (`WAVE_FORMAT_IEEE_FLOAT`).
+    F32,
+    /// 16-bit signed PCM (`WAVE_FORMAT_PCM`); input is clamped to [-1, 1].
+    I16,
+}
+
+impl WavSampleFormat {
+    fn bytes_per_sample(self) -> u16 {
+        match self {
+            WavSampleFormat::F32 => 4,
+            WavSampleFormat::I16 => 2,
+        }
+    }
+}
+
+// Byte offsets of the size fields that get patched on finalize.
+const RIFF_SIZE_OFFSET: u64 = 4;
+
+pub struct WavWriter<W: Write + Seek> {
+    inner: W,
+    format: WavSampleFormat,
+    channels: u16,
+    frames_written: u64,
+    data_bytes: u32,
+    fact_offset: Option<u64>,
+    data_size_offset: u64,
+    header_len: u32,
+    scratch: Vec<u8>,
+    finalized: bool,
+}
+
+impl WavWriter<BufWriter<File>> {
+    /// Create (or truncate) `path` and write a placeholder header.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        sample_rate: u32,
+        channels: u16,
+        format: WavSampleFormat,
+    ) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Self::new(BufWriter::new(file), sample_rate, channels, format)
+    }
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    /// Wrap `inner` and write a placeholder header at its current position.
+    pub fn new(
+        mut inner: W,
+        sample_rate: u32,
+        channels: u16,
+        format: WavSampleFormat,
+    ) -> io::Result<Self> {
+        if channels == 0 || sample_rate == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "wav writer needs non-zero channels and sample rate",
+            ));
+        }
+        let bytes_per_sample = format.bytes_per_sample();
+        let block_align = channels * bytes_per_sample;
+        let byte_rate = sample_rate * block_align as u32;
+
+        let mut h: Vec<u8> = Vec::with_capacity(58);
+        h.extend_from_slice(b"RIFF");
+        h.extend_from_slice(&0u32.to_le_bytes()); // patched
+        h.extend_from_slice(b"WAVE");
+        h.extend_from_slice(b"fmt ");
+        let format_tag: u16 = match format {
+            WavSampleFormat::I16 => 1,
+            WavSampleFormat::F32 => 3,
+        };
+        // Non-PCM formats carry a cbSize field (18-byte fmt chunk).
+        let fmt_len: u32 = if format_tag == 1 { 16 } else { 18 };
+        h.extend_from_slice(&fmt_len.to_le_bytes());
+        h.extend_from_slice(&format_tag.to_le_bytes());
+        h.extend_from_slice(&channels.to_le_bytes());
+        h.extend_from_slice(&sample_rate.to_le_bytes());
+        h.extend_from_slice(&byte_rate.to_le_bytes());
+        h.extend_from_slice(&block_align.to_le_bytes());
+        h.extend_from_slice(&(bytes_per_sample * 8).to_le_bytes());
+        if fmt_len == 18 {
+            h.extend_from_slice(&0u16.to_le_bytes());
+        }
+        // Non-PCM files should also carry a `fact` chunk with the frame count.
+        let fact_offset = if format_tag == 1 {
+            None
+        } else {
+            h.extend_from_slice(b"fact");
+            h.extend_from_slice(&4u32.to_le_bytes());
+            let off = h.len() as u64;
+            h.extend_from_slice(&0u32.to_le_bytes()); // patched
+            Some(off)
+        };
+        h.extend_from_slice(b"data");
+        let data_size_offset = h.len() as u64;
+        h.extend_from_slice(&0u32.to_le_bytes()); // patched
+
+        let base = inner.stream_position()?;
+        inner.write_all(&h)?;
+
+        Ok(Self {
+            inner,
+            format,
+            channels,
+            frames_written: 0,
+            data_bytes: 0,
+            fact_offset: fact_offset.map(|o| base + o),
+            data_size_offset: base + data_size_offset,
+            header_len: h.len() as u32,
+            scratch: Vec::new(),
+            finalized: false,
+        })
+    }
+
+    /// Append interleaved frames. `samples.len()` must be a multiple of the
+    /// channel count.
+    pub fn write_interleaved(&mut self, samples: &[f32]) -> io::Result<()> {
+        if self.finalized {
+            return Err(io::Error::other("wav writer already finalized"));
+        }
+        let channels = self.channels as usize;
+        if !samples.len().is_multiple_of(channels) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "sample count is not a whole number of frames",
+            ));
+        }
+        let bytes = samples.len() as u64 * self.format.bytes_per_sample() as u64;
+        // RIFF sizes are 32-bit; refuse to write past what the header can describe.
+        let limit = u32::MAX as u64 - self.header_len as u64;
+        if self.data_bytes as u64 + bytes > limit {
+            return Err(io::Error::other("wav data would exceed the 4 GiB RIFF limit"));
+        }
+
+        self.scratch.clear();
+        match self.format {
+            WavSampleFormat::F32 => {
+                for s in samples {
+                    self.scratch.extend_from_slice(&s.to_le_bytes());
+                }
+            }
+            WavSampleFormat::I16 => {
+                for s in samples {
+                    let v = (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+                    self.scratch.extend_from_slice(&v.to_le_bytes());
+                }
+            }
+        }
+        self.inner.write_all(&self.scratch)?;
+        self.data_bytes += bytes as u32;
+        self.frames_written += (samples.len() / channels) as u64;
+        Ok(())
+    }
+
+    pub fn frames_written(&self) -> u64 {
+        self.frames_written
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Patch the RIFF/`fact`/`data` sizes and flush. Safe to call more than
+    /// once; also attempted (errors ignored) on drop.
+    pub fn finalize(&mut self) -> io::Result<()> {
+        if self.finalized {
+            return Ok(());
+        }
+        let end = self.inner.stream_position()?;
+        let base = self.data_size_offset + 4 - self.header_len as u64;
+
+        // RIFF size excludes the 8-byte "RIFF"+size preamble.
+        let riff_size = self.header_len - 8 + self.data_bytes;
+        self.inner.seek(SeekFrom::Start(base + RIFF_SIZE_OFFSET))?;
+        self.inner.write_all(&riff_size.to_le_bytes())?;
+        if let Some(off) = self.fact_offset {
+            self.inner.seek(SeekFrom::Start(off))?;
+            self.inner.write_all(&(self.frames_written as u32).to_le_bytes())?;
+        }
+        self.inner.seek(SeekFrom::Start(self.data_size_offset))?;
+        self.inner.write_all(&self.data_bytes.to_le_bytes())?;
+        self.inner.seek(SeekFrom::Start(end))?;
+        self.inner.flush()?;
+        self.finalized = true;
+        Ok(())
+    }
+}
+
+impl<W: Write + Seek> Drop for WavWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.finalize();
+    }
+}
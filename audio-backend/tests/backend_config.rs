@@ -0,0 +1,113 @@
+use audio_backend::create_audio_backend_with_config;
+
+#[test]
+fn mock_reports_requested_rate_and_buffer_size() {
+    if !audio_backend::is_mock_backend_enabled() {
+        eprintln!("mock backend not enabled; skipping");
+        return;
+    }
+    let backend = create_audio_backend_with_config(Some(44100), Some(512)).expect("create backend");
+    assert_eq!(backend.sample_rate(), 44100);
+    assert_eq!(backend.buffer_size(), 512);
+
+    // Unset requests keep the defaults.
+    let backend = create_audio_backend_with_config(None, None).expect("create backend");
+    assert_eq!(backend.sample_rate(), 48000);
+    assert_eq!(backend.buffer_size(), 256);
+}
+
+// Real device; Windows-only like the other real-backend tests.
+#[cfg(target_os = "windows")]
+#[test]
+fn real_backend_reflects_honored_buffer_size() {
+    use audio_backend::{DiagnosticEvent, DiagnosticsCb};
+    use std::sync::{Arc, Mutex};
+
+    if audio_backend::is_mock_backend_enabled() {
+        return;
+    }
+    let requested = 512u32;
+    let mut backend = match create_audio_backend_with_config(None, Some(requested)) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!("no usable output device ({:?}); skipping", e);
+            return;
+        }
+    };
+
+    let events: Arc<Mutex<Vec<DiagnosticEvent>>> = Arc::new(Mutex::new(Vec::new()));
+    let events_cb = events.clone();
+    let cb: DiagnosticsCb = Arc::new(move |ev| events_cb.lock().unwrap().push(ev));
+    backend.set_diagnostics_callback(Some(cb));
+    backend.start(Arc::new(|out: &mut [f32], _sr, _frames| out.fill(0.0))).expect("start");
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    backend.stop().expect("stop");
+
+    let reported_change = events
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|e| matches!(e, DiagnosticEvent::BufferSizeChanged { .. }));
+    if reported_change {
+        // The device couldn't honor the request; the effective size must differ.
+        assert_ne!(backend.buffer_size(), requested as usize);
+    } else {
+        assert_eq!(backend.buffer_size(), requested as usize);
+    }
+}
+
+#[test]
+fn mock_output_latency_only_while_running() {
+    if !audio_backend::is_mock_backend_enabled() {
+        eprintln!("mock backend not enabled; skipping");
+        return;
+    }
+    let mut backend = create_audio_backend_with_config(None, None).expect("create backend");
+    assert_eq!(backend.output_latency(), None);
+
+    backend
+        .start(std::sync::Arc::new(|out: &mut [f32], _sr, _frames| out.fill(0.0)))
+        .expect("start");
+    let latency = backend.output_latency().expect("latency once running");
+    // 256 frames at 48 kHz.
+    assert!(latency > std::time::Duration::ZERO);
+    assert!(latency < std::time::Duration::from_millis(100), "implausible latency {:?}", latency);
+
+    backend.stop().expect("stop");
+    assert_eq!(backend.output_latency(), None);
+}
+
+// Forces an integer stream so the f32 -> i16 conversion path runs even on
+// devices whose default format is f32. Windows-only like the other real tests.
+#[cfg(all(target_os = "windows", not(feature = "mock-audio")))]
+#[test]
+fn real_backend_i16_stream_advances() {
+    use audio_backend::cpal_backend::CpalAudioBackend;
+    use audio_backend::AudioBackend;
+    use std::sync::Arc;
+
+    let mut backend = match CpalAudioBackend::with_sample_format(cpal::SampleFormat::I16) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!("no i16 output config available ({:?}); skipping", e);
+            return;
+        }
+    };
+    let sr = backend.sample_rate() as f32;
+    let channels = backend.channels() as usize;
+    let phase = Arc::new(std::sync::Mutex::new(0.0f32));
+    backend
+        .start(Arc::new(move |out: &mut [f32], _sr, _frames| {
+            let mut p = phase.lock().unwrap();
+            for frame in out.chunks_mut(channels) {
+                // Deliberately overshoot full scale to exercise clamping.
+                frame.fill(1.5 * (*p).sin());
+                *p = (*p + 2.0 * std::f32::consts::PI * 440.0 / sr) % (2.0 * std::f32::consts::PI);
+            }
+        }))
+        .expect("start");
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    let frames = backend.frames_since_start();
+    backend.stop().expect("stop");
+    assert!(frames > 0, "i16 stream did not advance");
+}
@@ -0,0 +1,70 @@
+use audio_backend::{create_audio_backend_for_device, list_output_device_names, list_output_devices, BackendError};
+
+#[test]
+fn mock_lists_single_default_device() {
+    if !audio_backend::is_mock_backend_enabled() {
+        eprintln!("mock backend not enabled; skipping");
+        return;
+    }
+    let devices = list_output_devices();
+    assert_eq!(devices.len(), 1);
+    assert!(devices[0].is_default);
+    assert_eq!(devices[0].name, "mock-device");
+}
+
+// Real-device enumeration; Windows-only like the other real-backend tests.
+#[cfg(target_os = "windows")]
+#[test]
+fn real_devices_report_at_most_one_default() {
+    if audio_backend::is_mock_backend_enabled() {
+        return;
+    }
+    let devices = list_output_devices();
+    if devices.is_empty() {
+        eprintln!("no output devices found; skipping");
+        return;
+    }
+    assert!(devices.iter().filter(|d| d.is_default).count() <= 1);
+    for d in &devices {
+        assert!(!d.name.is_empty());
+        for (min, max) in &d.sample_rates {
+            assert!(min <= max, "bad sample-rate range for {}", d.name);
+        }
+    }
+}
+
+#[test]
+fn mock_opens_device_by_name_and_rejects_unknown() {
+    if !audio_backend::is_mock_backend_enabled() {
+        eprintln!("mock backend not enabled; skipping");
+        return;
+    }
+    assert_eq!(list_output_device_names(), vec!["mock-device".to_string()]);
+    let backend = create_audio_backend_for_device("mock-device").expect("open mock device");
+    let provider = backend.as_device_info_provider().expect("device info");
+    assert_eq!(provider.get_device_name(), Some("mock-device"));
+    assert!(matches!(
+        create_audio_backend_for_device("no-such-device"),
+        Err(BackendError::DeviceNotFound)
+    ));
+}
+
+#[cfg(target_os = "windows")]
+#[test]
+fn real_device_opens_by_reported_name() {
+    if audio_backend::is_mock_backend_enabled() {
+        return;
+    }
+    let names = list_output_device_names();
+    let Some(first) = names.first() else {
+        eprintln!("no output devices found; skipping");
+        return;
+    };
+    let backend = create_audio_backend_for_device(first).expect("open device by name");
+    let provider = backend.as_device_info_provider().expect("device info");
+    assert_eq!(provider.get_device_name(), Some(first.as_str()));
+    assert!(matches!(
+        create_audio_backend_for_device("definitely-not-a-device"),
+        Err(BackendError::DeviceNotFound)
+    ));
+}
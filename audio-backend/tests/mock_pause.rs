@@ -0,0 +1,39 @@
+// The concrete mock is only compiled with the `mock-audio` feature.
+#![cfg(feature = "mock-audio")]
+
+use audio_backend::mock_backend::MockAudioBackend;
+use audio_backend::AudioBackend;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn paused_mock_holds_frames_and_skips_render() {
+    let mut backend = MockAudioBackend::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let calls_cb = calls.clone();
+    let mut out = Vec::new();
+
+    assert!(backend.pause().is_err(), "pause before start is an error");
+    assert!(!backend.render_block(&mut out), "nothing renders before start");
+
+    backend
+        .start(Arc::new(move |out: &mut [f32], _sr, _frames| {
+            calls_cb.fetch_add(1, Ordering::Relaxed);
+            out.fill(0.25);
+        }))
+        .expect("start");
+    assert!(backend.render_block(&mut out));
+    assert_eq!(backend.frames_since_start(), 256);
+    assert!(out.iter().all(|s| *s == 0.25));
+
+    backend.pause().expect("pause");
+    assert!(!backend.render_block(&mut out));
+    assert!(!backend.render_block(&mut out));
+    assert_eq!(backend.frames_since_start(), 256, "paused stream must not advance");
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    backend.resume().expect("resume");
+    assert!(backend.render_block(&mut out));
+    assert_eq!(backend.frames_since_start(), 512, "resume continues from the held count");
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+}
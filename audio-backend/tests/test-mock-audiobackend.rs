@@ -31,6 +31,10 @@ enum MockCtrlMsg {
     // has fully stopped rendering (i.e. no further render callbacks will occur).
     StopAck(Sender<()>),
     SetDiagnostics(Option<DiagnosticsCb>),
+    // Pause/Resume toggle `is_running` but keep the render function, and ack
+    // once applied so no simulated render slips in after pause() returns.
+    Pause(Sender<()>),
+    Resume(Sender<()>),
 }
 
 // A simple mock for `create_audio_backend()` for tests only.
@@ -88,6 +92,14 @@ impl MockAudioBackend {
                             }
                             MockCtrlMsg::SetRender(func) => render_fn = func,
                             MockCtrlMsg::SetDiagnostics(cb) => diag_cb = cb,
+                            MockCtrlMsg::Pause(ack) => {
+                                mock_state.lock().unwrap().is_running = false;
+                                let _ = ack.send(());
+                            }
+                            MockCtrlMsg::Resume(ack) => {
+                                mock_state.lock().unwrap().is_running = render_fn.is_some();
+                                let _ = ack.send(());
+                            }
                         }
                     }
                     Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
@@ -221,10 +233,30 @@ impl AudioBackend for MockAudioBackend {
         }
     }
 
+    fn pause(&mut self) -> Result<(), crate::BackendError> {
+        let (ack_tx, ack_rx) = bounded::<()>(1);
+        self.ctrl_tx.send(MockCtrlMsg::Pause(ack_tx)).unwrap();
+        ack_rx.recv_timeout(Duration::from_millis(500)).map_err(|_| BackendError::Other("mock worker did not ack pause".into()))
+    }
+
+    fn resume(&mut self) -> Result<(), crate::BackendError> {
+        let (ack_tx, ack_rx) = bounded::<()>(1);
+        self.ctrl_tx.send(MockCtrlMsg::Resume(ack_tx)).unwrap();
+        ack_rx.recv_timeout(Duration::from_millis(500)).map_err(|_| BackendError::Other("mock worker did not ack resume".into()))
+    }
+
     fn sample_rate(&self) -> u32 { 48000 }
     fn buffer_size(&self) -> usize { 1024 }
     fn channels(&self) -> u16 { 2 }
     fn frames_since_start(&self) -> u64 { self.shared_state.lock().unwrap().frames_since_start }
+    fn output_latency(&self) -> Option<Duration> {
+        // One simulated 512-frame stereo buffer at 48 kHz while running.
+        if self.shared_state.lock().unwrap().is_running {
+            Some(Duration::from_secs_f64(512.0 / 48000.0))
+        } else {
+            None
+        }
+    }
     fn set_diagnostics_callback(&mut self, cb: Option<crate::DiagnosticsCb>) {
         self.ctrl_tx.send(MockCtrlMsg::SetDiagnostics(cb)).unwrap();
     }
@@ -366,6 +398,33 @@ mod tests {
     assert_eq!(events.len(), 1); // No new events should have been added.
     }
 
+    #[test]
+    fn test_pause_holds_frames_and_resume_continues() {
+        let mut backend = MockAudioBackend::new();
+        backend.start(Arc::new(test_render_fn)).unwrap();
+        wait_for_mock_processing();
+        assert!(backend.frames_since_start() > 0);
+
+        backend.pause().unwrap();
+        let paused_at = backend.frames_since_start();
+        wait_for_mock_processing();
+        assert_eq!(backend.frames_since_start(), paused_at);
+
+        // No new start(): the retained render function picks up again.
+        backend.resume().unwrap();
+        wait_for_mock_processing();
+        assert!(backend.frames_since_start() > paused_at);
+        backend.stop().unwrap();
+    }
+
+    #[test]
+    fn test_resume_without_start_does_not_render() {
+        let mut backend = MockAudioBackend::new();
+        backend.resume().unwrap();
+        wait_for_mock_processing();
+        assert_eq!(backend.frames_since_start(), 0);
+    }
+
     #[test]
     fn test_render_fn_panic_is_caught() {
         let mut backend = create_audio_backend().unwrap();
@@ -31,6 +31,8 @@ struct CpalBackendInner {
     frames: AtomicU64,
     // Diagnostics callback (worker uses this via a clone of the Arc)
     diagnostics: Arc<ArcSwapOption<DiagnosticsCb>>,
+    // Latest output latency estimate in nanoseconds; 0 = not running yet.
+    latency_ns: AtomicU64,
     // Control channel sender to worker.
     ctrl_tx: Sender<CtrlMsg>,
 }
@@ -42,6 +44,9 @@ enum CtrlMsg {
     // that rendering has fully stopped (no further data callbacks will occur).
     StopAck(Sender<()>),
     SetDiagnostics(Option<DiagnosticsCb>),
+    // Pause/Resume act on the retained stream and reply with the outcome.
+    Pause(Sender<Result<(), BackendError>>),
+    Resume(Sender<Result<(), BackendError>>),
 }
 
 impl CpalAudioBackend {
@@ -86,6 +91,7 @@ impl CpalAudioBackend {
             render: Arc::new(ArcSwapOption::from(None)),
             frames: AtomicU64::new(0),
             diagnostics: Arc::new(ArcSwapOption::from(None)),
+            latency_ns: AtomicU64::new(0),
             ctrl_tx: tx.clone(),
         });
 
@@ -113,7 +119,12 @@ fn worker_loop(device: Device, config: StreamConfig, rx: Receiver<CtrlMsg>, inne
                         inner.render.store(opt.map(Arc::new));
                     }
                     CtrlMsg::Start => {
-                        if stream_opt.is_none() {
+                        if let Some(s) = stream_opt.as_ref() {
+                            // A paused stream is still around; restart it rather than rebuilding.
+                            if let Err(e) = s.play() {
+                                eprintln!("Failed to play stream: {}", e);
+                            }
+                        } else {
                             // Clone `inner` for the error callback.
                             let inner_for_err_cb = inner.clone();
                             let err_cb = move |err| {
@@ -129,7 +140,7 @@ fn worker_loop(device: Device, config: StreamConfig, rx: Receiver<CtrlMsg>, inne
                             let channels_local = channels;
                             let sample_rate = config.sample_rate.0;
 
-                            let data_cb = move |data: &mut [f32], _info: &cpal::OutputCallbackInfo| {
+                            let data_cb = move |data: &mut [f32], info: &cpal::OutputCallbackInfo| {
                                 let opt_render = inner_for_data_cb.render.load();
                                 if let Some(render) = opt_render.as_ref() {
                                     let frames = data.len() / channels_local;
@@ -145,6 +156,14 @@ fn worker_loop(device: Device, config: StreamConfig, rx: Receiver<CtrlMsg>, inne
 
                                 let frames_written = (data.len() / channels_local) as u64;
                                 inner_for_data_cb.frames.fetch_add(frames_written, Ordering::Relaxed);
+
+                                // Prefer the host's callback->playback timestamps; fall back to
+                                // one buffer's worth of audio when they aren't meaningful.
+                                let ts = info.timestamp();
+                                let latency = ts.playback.duration_since(&ts.callback)
+                                    .filter(|d| !d.is_zero())
+                                    .unwrap_or_else(|| std::time::Duration::from_secs_f64(frames_written as f64 / sample_rate as f64));
+                                inner_for_data_cb.latency_ns.store(latency.as_nanos() as u64, Ordering::Relaxed);
                             };
 
                             match device.build_output_stream(&config, data_cb, err_cb, None) {
@@ -168,6 +187,7 @@ fn worker_loop(device: Device, config: StreamConfig, rx: Receiver<CtrlMsg>, inne
                     CtrlMsg::StopAck(ack) => {
                         // Drop the stream to stop callbacks.
                         stream_opt = None;
+                        inner.latency_ns.store(0, Ordering::Relaxed);
 
                         // Wait until frames counter stabilizes (no further frames written)
                         // so callers can deterministically know no more callbacks will occur.
@@ -196,6 +216,20 @@ fn worker_loop(device: Device, config: StreamConfig, rx: Receiver<CtrlMsg>, inne
                     CtrlMsg::SetDiagnostics(cb) => {
                         inner.diagnostics.store(cb.map(Arc::new));
                     }
+                    CtrlMsg::Pause(reply) => {
+                        let res = match stream_opt.as_ref() {
+                            Some(s) => s.pause().map_err(|e| BackendError::PlaybackError(e.to_string())),
+                            None => Err(BackendError::Other("stream not started".into())),
+                        };
+                        let _ = reply.send(res);
+                    }
+                    CtrlMsg::Resume(reply) => {
+                        let res = match stream_opt.as_ref() {
+                            Some(s) => s.play().map_err(|e| BackendError::PlaybackError(e.to_string())),
+                            None => Err(BackendError::Other("stream not started".into())),
+                        };
+                        let _ = reply.send(res);
+                    }
                     // Worker exits when the control channel is disconnected.
                 }
             }
@@ -221,10 +255,28 @@ impl AudioBackend for CpalAudioBackend {
     Ok(())
     }
 
+    fn pause(&mut self) -> Result<(), BackendError> {
+        let (reply_tx, reply_rx) = bounded(1);
+        self.inner.ctrl_tx.send(CtrlMsg::Pause(reply_tx)).map_err(|_| BackendError::Other("ctrl channel closed".into()))?;
+        reply_rx.recv_timeout(std::time::Duration::from_millis(500)).map_err(|_| BackendError::Other("worker did not reply".into()))?
+    }
+
+    fn resume(&mut self) -> Result<(), BackendError> {
+        let (reply_tx, reply_rx) = bounded(1);
+        self.inner.ctrl_tx.send(CtrlMsg::Resume(reply_tx)).map_err(|_| BackendError::Other("ctrl channel closed".into()))?;
+        reply_rx.recv_timeout(std::time::Duration::from_millis(500)).map_err(|_| BackendError::Other("worker did not reply".into()))?
+    }
+
     fn sample_rate(&self) -> u32 { self.inner.info.sample_rate }
     fn buffer_size(&self) -> usize { self.inner.info.buffer_size }
     fn channels(&self) -> u16 { self.inner.info.channels }
     fn frames_since_start(&self) -> u64 { self.inner.frames.load(Ordering::Relaxed) }
+    fn output_latency(&self) -> Option<std::time::Duration> {
+        match self.inner.latency_ns.load(Ordering::Relaxed) {
+            0 => None,
+            ns => Some(std::time::Duration::from_nanos(ns)),
+        }
+    }
     fn set_diagnostics_callback(&mut self, cb: Option<DiagnosticsCb>) {
         self.inner.ctrl_tx.send(CtrlMsg::SetDiagnostics(cb)).ok();
     }
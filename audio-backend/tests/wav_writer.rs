@@ -0,0 +1,136 @@
+use std::io::Cursor;
+
+use audio_backend::{WavSampleFormat, WavWriter};
+
+fn u32_at(b: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes([b[off], b[off + 1], b[off + 2], b[off + 3]])
+}
+
+fn u16_at(b: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([b[off], b[off + 1]])
+}
+
+/// Walk the RIFF chunks and return (fmt chunk offset, data offset, data len).
+fn chunks(b: &[u8]) -> (usize, usize, usize) {
+    assert_eq!(&b[0..4], b"RIFF");
+    assert_eq!(u32_at(b, 4) as usize, b.len() - 8, "RIFF size");
+    assert_eq!(&b[8..12], b"WAVE");
+    let mut pos = 12;
+    let mut fmt = None;
+    while pos + 8 <= b.len() {
+        let id = &b[pos..pos + 4];
+        let len = u32_at(b, pos + 4) as usize;
+        if id == b"fmt " {
+            fmt = Some(pos + 8);
+        }
+        if id == b"data" {
+            return (fmt.expect("fmt before data"), pos + 8, len);
+        }
+        pos += 8 + len;
+    }
+    panic!("no data chunk");
+}
+
+#[test]
+fn f32_writer_patches_sizes_after_streaming() {
+    let mut w = WavWriter::new(Cursor::new(Vec::new()), 48000, 2, WavSampleFormat::F32).unwrap();
+    let block: Vec<f32> = (0..256).map(|i| i as f32 / 256.0).collect();
+    for _ in 0..3 {
+        w.write_interleaved(&block).unwrap();
+    }
+    w.finalize().unwrap();
+    assert_eq!(w.frames_written(), 384);
+
+    let bytes = w.get_ref().get_ref().clone();
+    let (fmt, data, data_len) = chunks(&bytes);
+    assert_eq!(u16_at(&bytes, fmt), 3); // IEEE float
+    assert_eq!(u16_at(&bytes, fmt + 2), 2);
+    assert_eq!(u32_at(&bytes, fmt + 4), 48000);
+    assert_eq!(u16_at(&bytes, fmt + 14), 32);
+    assert_eq!(data_len, 384 * 2 * 4);
+    assert_eq!(data + data_len, bytes.len());
+    let second = f32::from_le_bytes(bytes[data + 4..data + 8].try_into().unwrap());
+    assert_eq!(second, 1.0 / 256.0);
+}
+
+#[test]
+fn i16_writer_clamps_and_uses_pcm_header() {
+    let mut w = WavWriter::new(Cursor::new(Vec::new()), 44100, 1, WavSampleFormat::I16).unwrap();
+    w.write_interleaved(&[0.0, 2.0, -2.0]).unwrap();
+    w.finalize().unwrap();
+
+    let bytes = w.get_ref().get_ref().clone();
+    assert_eq!(bytes.len(), 44 + 6);
+    let (fmt, data, data_len) = chunks(&bytes);
+    assert_eq!(u16_at(&bytes, fmt), 1); // PCM
+    assert_eq!(u16_at(&bytes, fmt + 14), 16);
+    assert_eq!(data_len, 6);
+    assert_eq!(u16_at(&bytes, data + 2) as i16, i16::MAX);
+    assert_eq!(u16_at(&bytes, data + 4) as i16, -i16::MAX);
+}
+
+#[test]
+fn partial_frames_are_rejected() {
+    let mut w = WavWriter::new(Cursor::new(Vec::new()), 48000, 2, WavSampleFormat::F32).unwrap();
+    assert!(w.write_interleaved(&[0.0; 3]).is_err());
+    assert_eq!(w.frames_written(), 0);
+}
+
+/// Decode `bytes` with symphonia, independently of the RIFF walker above.
+/// Returns (sample_rate, channels, interleaved f32 samples).
+fn decode_with_symphonia(bytes: Vec<u8>) -> (u32, usize, Vec<f32>) {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::DecoderOptions;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    let mss = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension("wav");
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .expect("symphonia should recognise the file as WAV");
+    let mut format = probed.format;
+    let track = format.default_track().expect("track").clone();
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .expect("decoder");
+    let mut samples = Vec::new();
+    while let Ok(packet) = format.next_packet() {
+        let decoded = decoder.decode(&packet).expect("decode");
+        let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+        buf.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buf.samples());
+    }
+    (
+        track.codec_params.sample_rate.expect("rate"),
+        track.codec_params.channels.expect("channels").count(),
+        samples,
+    )
+}
+
+#[test]
+fn output_round_trips_through_an_independent_decoder() {
+    let block: Vec<f32> = (0..600).map(|i| ((i as f32) * 0.01).sin() * 0.8).collect();
+
+    let mut w = WavWriter::new(Cursor::new(Vec::new()), 44100, 2, WavSampleFormat::F32).unwrap();
+    w.write_interleaved(&block).unwrap();
+    w.write_interleaved(&block).unwrap();
+    w.finalize().unwrap();
+    let (sr, ch, decoded) = decode_with_symphonia(w.get_ref().get_ref().clone());
+    assert_eq!((sr, ch), (44100, 2));
+    assert_eq!(decoded.len(), block.len() * 2);
+    assert!(decoded.iter().zip(block.iter().cycle()).all(|(d, s)| d == s), "f32 samples must survive bit-exact");
+
+    let mut w = WavWriter::new(Cursor::new(Vec::new()), 48000, 1, WavSampleFormat::I16).unwrap();
+    w.write_interleaved(&block).unwrap();
+    w.finalize().unwrap();
+    let (sr, ch, decoded) = decode_with_symphonia(w.get_ref().get_ref().clone());
+    assert_eq!((sr, ch), (48000, 1));
+    assert_eq!(decoded.len(), block.len());
+    for (d, s) in decoded.iter().zip(&block) {
+        assert!((d - s).abs() < 2.0 / 32768.0, "i16 sample {} decoded as {}", s, d);
+    }
+}
@@ -0,0 +1,29 @@
+// The concrete mock is only compiled with the `mock-audio` feature.
+#![cfg(feature = "mock-audio")]
+
+use audio_backend::mock_backend::MockAudioBackend;
+use audio_backend::{AudioBackend, DiagnosticEvent, DiagnosticsCb};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn injected_xruns_accumulate_and_reach_callback() {
+    let mut backend = MockAudioBackend::new();
+    assert_eq!(backend.xrun_count(), 0);
+
+    let events: Arc<Mutex<Vec<DiagnosticEvent>>> = Arc::new(Mutex::new(Vec::new()));
+    let events_cb = events.clone();
+    let cb: DiagnosticsCb = Arc::new(move |ev| events_cb.lock().unwrap().push(ev));
+    backend.set_diagnostics_callback(Some(cb));
+
+    backend.inject_xrun();
+    backend.inject_xrun();
+    assert_eq!(backend.xrun_count(), 2);
+    // Delivered synchronously; no need to wait for a spawned thread.
+    assert_eq!(events.lock().unwrap().len(), 2);
+
+    // The counter keeps counting after the callback is removed.
+    backend.set_diagnostics_callback(None);
+    backend.inject_xrun();
+    assert_eq!(backend.xrun_count(), 3);
+    assert_eq!(events.lock().unwrap().len(), 2);
+}
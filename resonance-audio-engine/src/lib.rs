@@ -1,9 +1,11 @@
 mod bridge;
 pub mod example;
 pub mod renderer;
+pub mod smoothing;
 pub mod spatializer;
 pub mod types;
 
 pub use renderer::Renderer;
+pub use smoothing::SmoothedParam;
 pub use spatializer::Spatializer;
 pub use types::*;
@@ -8,11 +8,20 @@ use asset_manager::sfx_loader::SfxMetadata;
 use ringbuf::traits::Consumer;
 use ringbuf::HeapCons;
 
+use crate::smoothing::{ms_to_frames, SmoothedParam};
+
 // ringbuffer implementation is provided by the `ringbuf` crate via HeapRb/HeapCons
 
 // ---------- Config ----------
 const MAX_SOURCES: usize = 256; // pool size (tunable)
 const CMD_QUEUE_CAP: usize = 1024; // bounded command queue
+// Stride of the cheap non-finite output check; the `nan-guard-full` feature
+// checks every sample instead.
+const NAN_GUARD_STRIDE: usize = 16;
+// Upper bound for per-voice gain (+12 dB); larger requests are clamped.
+const MAX_VOICE_GAIN: f32 = 4.0;
+// Default ramp applied to voice gain changes to avoid zipper noise/clicks.
+const DEFAULT_GAIN_SMOOTHING_MS: f32 = 5.0;
 
 // ---------- Types ----------
 #[derive(Debug, Clone)]
@@ -21,6 +30,15 @@ pub struct SfxBuffer {
     pub meta: SfxMetadata,
 }
 
+/// A procedural source (wind, engine noise, ...) mixed like any other voice.
+///
+/// `render` is called on the RT thread once per block and must not block or
+/// allocate. `out` is interleaved at the renderer's channel count and must be
+/// fully overwritten; the renderer applies the voice gain afterwards.
+pub trait SourceGenerator: Send + Sync {
+    fn render(&self, out: &mut [f32], sample_rate: u32);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,6 +68,53 @@ mod tests {
             "scratch len should be at least initial"
         );
     }
+
+    #[test]
+    fn flush_commands_discards_pending_queue() {
+        let q = ArrayQueue::new(8);
+        for slot in 0..5 {
+            assert!(q.push(Command::StopVoice { slot }).is_ok());
+        }
+        assert_eq!(flush_commands(&q), 5);
+        assert!(q.is_empty());
+        assert_eq!(flush_commands(&q), 0);
+    }
+
+    #[test]
+    fn sanitize_gain_clamps_non_finite_and_out_of_range() {
+        assert_eq!(sanitize_gain(f32::NAN), 0.0);
+        assert_eq!(sanitize_gain(f32::INFINITY), MAX_VOICE_GAIN);
+        assert_eq!(sanitize_gain(f32::NEG_INFINITY), 0.0);
+        assert_eq!(sanitize_gain(-0.5), 0.0);
+        assert_eq!(sanitize_gain(0.75), 0.75);
+    }
+}
+
+/// Clamp a requested voice gain to `[0, MAX_VOICE_GAIN]`. NaN maps to silence
+/// so a bad value from gameplay can never poison the mix.
+fn sanitize_gain(gain: f32) -> f32 {
+    if gain.is_nan() {
+        0.0
+    } else {
+        gain.clamp(0.0, MAX_VOICE_GAIN)
+    }
+}
+
+/// Discard every command still waiting in `queue` without applying it.
+///
+/// Intended for the game thread after a scene transition so stale
+/// gain/stop commands for the previous scene never reach the renderer.
+/// This does not race destructively with `process_output_interleaved`:
+/// `ArrayQueue::pop` hands each command to exactly one caller, so a command
+/// is either applied by the RT drain or dropped here, never both. Dropping
+/// happens on the calling thread, which keeps deallocation (e.g. stream
+/// ring consumers) off the RT path. Returns the number of commands dropped.
+pub fn flush_commands(queue: &ArrayQueue<Command>) -> usize {
+    let mut dropped = 0usize;
+    while queue.pop().is_some() {
+        dropped += 1;
+    }
+    dropped
 }
 
 pub enum Command {
@@ -59,13 +124,50 @@ pub enum Command {
         gain: f32,
         pos: Option<Vec3>,
     },
+    /// `PlaySfx` that stays silent until the renderer's stream time reaches
+    /// `start_frame`, then starts on that exact frame. A `start_frame` already
+    /// in the past starts at the beginning of the next block.
+    PlaySfxAt {
+        slot: usize,
+        buffer: SfxBuffer,
+        gain: f32,
+        pos: Option<Vec3>,
+        start_frame: u64,
+    },
+    /// Start a custom generator in `slot`; it plays until `StopVoice`.
+    PlayGenerator {
+        slot: usize,
+        generator: Arc<dyn SourceGenerator>,
+        gain: f32,
+        pos: Option<Vec3>,
+    },
     StopVoice {
         slot: usize,
     },
+    /// Ramp the voice's gain to zero over `fade_ms`, then stop it as
+    /// `StopVoice` would. A later `SetVoiceGain` cancels the pending stop.
+    FadeOutVoice {
+        slot: usize,
+        fade_ms: f32,
+    },
     SetVoiceGain {
         slot: usize,
         gain: f32,
     },
+    /// Gate a voice to silence without touching its gain, so unmuting
+    /// restores the exact previous level.
+    SetVoiceMuted {
+        slot: usize,
+        muted: bool,
+    },
+    /// Hold a voice where it is: it outputs silence and its playhead (or
+    /// generator) does not advance until `ResumeVoice`.
+    PauseVoice {
+        slot: usize,
+    },
+    ResumeVoice {
+        slot: usize,
+    },
     StartStream {
         slot: usize,
         ring: HeapCons<f32>,
@@ -85,14 +187,35 @@ pub enum Command {
         position: Vec3,
         rotation: Quat,
     },
+    /// Global volume applied to the final mix (local voices, streams and the
+    /// resonance output). Clamped to `[0, 1]`.
+    SetMasterGain {
+        gain: f32,
+    },
+    /// Trim on the resonance (natively spatialized) output; see
+    /// [`Renderer::set_resonance_gain`].
+    SetResonanceGain {
+        gain: f32,
+    },
 }
 
 pub struct Voice {
     active: AtomicBool,
     sfx: Option<Arc<Vec<f32>>>,
     meta: Option<SfxMetadata>,
+    generator: Option<Arc<dyn SourceGenerator>>,
     playhead: usize,
-    gain: f32,
+    gain: SmoothedParam,
+    // 1.0 when audible, 0.0 when muted; ramped like gain
+    mute_gate: SmoothedParam,
+    // false until the voice has been mixed once; gain changes before that snap
+    rendered: bool,
+    // skipped by the mixer without losing its position
+    paused: bool,
+    // stop once a fade-out has brought `gain` to zero
+    stop_when_silent: bool,
+    // stream frame the voice starts on (0 = as soon as it is drained)
+    start_frame: u64,
     spatial_src_id: Option<i32>,
 }
 
@@ -102,13 +225,36 @@ impl Default for Voice {
             active: AtomicBool::new(false),
             sfx: None,
             meta: None,
+            generator: None,
             playhead: 0,
-            gain: 1.0,
+            gain: SmoothedParam::new(1.0),
+            mute_gate: SmoothedParam::new(1.0),
+            rendered: false,
+            paused: false,
+            stop_when_silent: false,
+            start_frame: 0,
             spatial_src_id: None,
         }
     }
 }
 
+impl Voice {
+    fn stop(&mut self) {
+        self.active.store(false, Ordering::Release);
+        self.sfx = None;
+        self.meta = None;
+        self.generator = None;
+        self.playhead = 0;
+        self.paused = false;
+        self.stop_when_silent = false;
+        self.start_frame = 0;
+    }
+
+    fn faded_out(&self) -> bool {
+        self.stop_when_silent && self.gain.is_settled() && self.gain.current() == 0.0
+    }
+}
+
 #[derive(Default)]
 pub struct StreamSlot {
     ring: Option<HeapCons<f32>>,
@@ -119,6 +265,7 @@ pub struct StreamSlot {
 // ---------- Renderer ----------
 pub struct Renderer {
     api: Api,
+    sample_rate: u32,
     num_channels: usize,
     frames_per_buffer: usize,
 
@@ -130,6 +277,20 @@ pub struct Renderer {
     cmd_queue: Arc<ArrayQueue<Command>>,
     // preallocated scratch to avoid allocations in RT path
     stream_scratch: Vec<f32>,
+    generator_scratch: Vec<f32>,
+    resonance_scratch: Vec<f32>,
+
+    gain_ramp_frames: u32,
+    // trim applied to the resonance fill before it is summed with local voices
+    resonance_gain: SmoothedParam,
+    master_gain: SmoothedParam,
+    output_started: bool,
+    // frames rendered since construction; the clock for `PlaySfxAt`
+    stream_time_frames: u64,
+    // output NaN/Inf guard
+    nan_guard: bool,
+    nan_guard_offset: usize,
+    non_finite_samples: u64,
 }
 
 impl Renderer {
@@ -144,6 +305,7 @@ impl Renderer {
 
         Self {
             api,
+            sample_rate: sample_rate_hz.max(0) as u32,
             num_channels,
             frames_per_buffer,
             voices,
@@ -152,13 +314,77 @@ impl Renderer {
             cmd_queue: Arc::new(ArrayQueue::new(CMD_QUEUE_CAP)),
             // preallocate stream scratch to avoid heap allocs in RT path
             stream_scratch: vec![0.0f32; frames_per_buffer * num_channels],
+            generator_scratch: vec![0.0f32; frames_per_buffer * num_channels],
+            resonance_scratch: vec![0.0f32; frames_per_buffer * num_channels],
+            gain_ramp_frames: ms_to_frames(DEFAULT_GAIN_SMOOTHING_MS, sample_rate_hz.max(0) as u32),
+            resonance_gain: SmoothedParam::new(1.0),
+            master_gain: SmoothedParam::new(1.0),
+            output_started: false,
+            stream_time_frames: 0,
+            nan_guard: true,
+            nan_guard_offset: 0,
+            non_finite_samples: 0,
+        }
+    }
+
+    /// Gain applied to the resonance (natively spatialized) output before it
+    /// is summed with locally mixed voices/streams. Ramped like voice gain once
+    /// output has started.
+    ///
+    /// For setup before the renderer moves into the audio callback; after
+    /// that, push `Command::SetResonanceGain` instead.
+    pub fn set_resonance_gain(&mut self, gain: f32) {
+        let gain = sanitize_gain(gain);
+        if self.output_started {
+            self.resonance_gain.set_target(gain, self.gain_ramp_frames);
+        } else {
+            self.resonance_gain.snap(gain);
         }
     }
 
+    /// `set_resonance_gain` in decibels, e.g. `-3.0` to trim the spatial bed.
+    /// Pre-start setup only, like `set_resonance_gain`.
+    pub fn set_resonance_gain_db(&mut self, db: f32) {
+        self.set_resonance_gain(10f32.powf(db / 20.0));
+    }
+
+    /// Set the ramp time applied when a playing voice's gain changes.
+    pub fn set_gain_smoothing_ms(&mut self, ms: f32) {
+        self.gain_ramp_frames = ms_to_frames(ms, self.sample_rate);
+    }
+
+    /// Frames rendered so far: the start of the next block on the stream clock.
+    pub fn stream_time_frames(&self) -> u64 {
+        self.stream_time_frames
+    }
+
+    /// `stream_time_frames` in seconds at the renderer's sample rate.
+    pub fn stream_time_secs(&self) -> f64 {
+        if self.sample_rate == 0 {
+            return 0.0;
+        }
+        self.stream_time_frames as f64 / self.sample_rate as f64
+    }
+
+    /// Enable or disable the output NaN/Inf guard (enabled by default).
+    pub fn set_nan_guard(&mut self, enabled: bool) {
+        self.nan_guard = enabled;
+    }
+
+    /// Debug: total number of non-finite output samples replaced with silence.
+    pub fn debug_non_finite_count(&self) -> u64 {
+        self.non_finite_samples
+    }
+
     pub fn command_sender(&self) -> Arc<ArrayQueue<Command>> {
         self.cmd_queue.clone()
     }
 
+    /// Discard all queued, not-yet-applied commands. See [`flush_commands`].
+    pub fn flush_commands(&self) -> usize {
+        flush_commands(&self.cmd_queue)
+    }
+
     /// Debug: count active voices (threadsafe)
     pub fn debug_active_voice_count(&self) -> usize {
         self.voices
@@ -192,7 +418,11 @@ impl Renderer {
 
     pub fn alloc_slot(&mut self) -> Option<usize> {
         for (i, v) in self.voices.iter().enumerate() {
-            if !v.active.load(Ordering::Acquire) && v.sfx.is_none() && self.sources[i].is_none() {
+            if !v.active.load(Ordering::Acquire)
+                && v.sfx.is_none()
+                && v.generator.is_none()
+                && self.sources[i].is_none()
+            {
                 return Some(i);
             }
         }
@@ -237,16 +467,32 @@ impl Renderer {
                 buffer,
                 gain,
                 pos,
+            } => self.apply_command(Command::PlaySfxAt {
+                slot,
+                buffer,
+                gain,
+                pos,
+                start_frame: 0,
+            }),
+            Command::PlaySfxAt {
+                slot,
+                buffer,
+                gain,
+                pos,
+                start_frame,
             } => {
                 if slot < self.voices.len() {
                     let v = &mut self.voices[slot];
-                    // Lightweight log for diagnostics: indicate PlaySfx application
-                    println!("[renderer] apply_command: PlaySfx -> slot={} sr={} ch={} gain={}",
-                        slot, buffer.meta.sample_rate, buffer.meta.channels, gain);
                     v.sfx = Some(buffer.samples.clone());
                     v.meta = Some(buffer.meta.clone());
+                    v.generator = None;
                     v.playhead = 0;
-                    v.gain = gain;
+                    v.gain.snap(sanitize_gain(gain));
+                    v.mute_gate.snap(1.0);
+                    v.rendered = false;
+                    v.paused = false;
+                    v.stop_when_silent = false;
+                    v.start_frame = start_frame;
                     v.active.store(true, Ordering::Release);
                     if let Some(position) = pos {
                         if let Some(Some(src)) = self.sources.get(slot) {
@@ -256,18 +502,73 @@ impl Renderer {
                     }
                 }
             }
-            Command::StopVoice { slot } => {
+            Command::PlayGenerator {
+                slot,
+                generator,
+                gain,
+                pos,
+            } => {
                 if slot < self.voices.len() {
                     let v = &mut self.voices[slot];
-                    v.active.store(false, Ordering::Release);
                     v.sfx = None;
                     v.meta = None;
+                    v.generator = Some(generator);
                     v.playhead = 0;
+                    v.gain.snap(sanitize_gain(gain));
+                    v.mute_gate.snap(1.0);
+                    v.rendered = false;
+                    v.paused = false;
+                    v.stop_when_silent = false;
+                    v.start_frame = 0;
+                    v.active.store(true, Ordering::Release);
+                    if let Some(position) = pos {
+                        if let Some(Some(src)) = self.sources.get(slot) {
+                            self.api
+                                .set_source_position(*src, position.x, position.y, position.z);
+                        }
+                    }
+                }
+            }
+            Command::StopVoice { slot } => {
+                if let Some(v) = self.voices.get_mut(slot) {
+                    v.stop();
+                }
+            }
+            Command::FadeOutVoice { slot, fade_ms } => {
+                if let Some(v) = self.voices.get_mut(slot) {
+                    if v.rendered {
+                        v.gain.set_target(0.0, ms_to_frames(fade_ms, self.sample_rate));
+                        v.stop_when_silent = true;
+                    } else {
+                        // Never heard, so there is nothing to fade.
+                        v.stop();
+                    }
                 }
             }
             Command::SetVoiceGain { slot, gain } => {
                 if slot < self.voices.len() {
-                    self.voices[slot].gain = gain;
+                    let v = &mut self.voices[slot];
+                    v.stop_when_silent = false;
+                    // Nothing has been heard yet, so there is nothing to click against.
+                    let ramp = if v.rendered { self.gain_ramp_frames } else { 0 };
+                    v.gain.set_target(sanitize_gain(gain), ramp);
+                }
+            }
+            Command::SetVoiceMuted { slot, muted } => {
+                if slot < self.voices.len() {
+                    let v = &mut self.voices[slot];
+                    let ramp = if v.rendered { self.gain_ramp_frames } else { 0 };
+                    v.mute_gate.set_target(if muted { 0.0 } else { 1.0 }, ramp);
+                }
+            }
+            Command::PauseVoice { slot } => {
+                if let Some(v) = self.voices.get_mut(slot) {
+                    v.paused = true;
+                }
+            }
+            Command::ResumeVoice { slot } => {
+                if let Some(v) = self.voices.get_mut(slot) {
+                    v.paused = false;
                 }
             }
             Command::StartStream {
@@ -294,6 +595,15 @@ impl Renderer {
                 self.api
                     .set_head_rotation(rotation.x, rotation.y, rotation.z, rotation.w);
             }
+            Command::SetResonanceGain { gain } => self.set_resonance_gain(gain),
+            Command::SetMasterGain { gain } => {
+                let gain = sanitize_gain(gain).min(1.0);
+                if self.output_started {
+                    self.master_gain.set_target(gain, self.gain_ramp_frames);
+                } else {
+                    self.master_gain.snap(gain);
+                }
+            }
         }
     }
 
@@ -317,37 +627,84 @@ impl Renderer {
             *sample = 0.0;
         }
 
+        let scratch_len = num_frames * self.num_channels;
+        if self.generator_scratch.len() < scratch_len {
+            self.generator_scratch.resize(scratch_len, 0.0f32);
+        }
+
+        let block_start = self.stream_time_frames;
         for v in &mut self.voices {
-            if !v.active.load(Ordering::Acquire) {
+            if !v.active.load(Ordering::Acquire) || v.paused {
+                continue;
+            }
+            // Scheduled voices wait, then start mid-block on their exact frame.
+            let start_offset = v.start_frame.saturating_sub(block_start);
+            if start_offset >= num_frames as u64 {
+                continue;
+            }
+            v.rendered = true;
+            if let Some(ref generator) = v.generator {
+                let scratch = &mut self.generator_scratch[..scratch_len];
+                generator.render(scratch, self.sample_rate);
+                let channels = self.num_channels;
+                for (dst, src) in buffer[..scratch_len]
+                    .chunks_exact_mut(channels)
+                    .zip(scratch.chunks_exact(channels))
+                {
+                    let g = v.gain.next() * v.mute_gate.next();
+                    for (d, s) in dst.iter_mut().zip(src.iter()) {
+                        *d += *s * g;
+                    }
+                }
+                if v.faded_out() {
+                    v.stop();
+                }
                 continue;
             }
             if let Some(ref sfx_arc) = v.sfx {
                 let samples = &**sfx_arc;
                 if let Some(ref meta) = v.meta {
                     let channels = meta.channels as usize;
-                    let frames_available =
-                        (samples.len() / channels).saturating_sub(v.playhead / channels);
-                    let frames_to_mix = frames_available.min(num_frames);
-                    for frame in 0..frames_to_mix {
-                        let src_base = v.playhead + frame * channels;
-                        let dst_base = frame * self.num_channels;
-                        for ch in 0..channels.min(self.num_channels) {
-                            buffer[dst_base + ch] += samples[src_base + ch] * v.gain;
+                    let total_frames = samples.len() / channels;
+                    // Loop points are [start, end) in frames; playback runs to
+                    // `end` then wraps to `start` until the voice is stopped.
+                    let loop_region = meta
+                        .loop_points
+                        .map(|(start, end)| (start as usize, (end as usize).min(total_frames)))
+                        .filter(|(start, end)| start < end);
+                    let mut pos = v.playhead / channels;
+                    let mut mixed = start_offset as usize;
+                    while mixed < num_frames {
+                        let end = match loop_region {
+                            Some((_, loop_end)) if pos < loop_end => loop_end,
+                            _ => total_frames,
+                        };
+                        let run = end.saturating_sub(pos).min(num_frames - mixed);
+                        for frame in 0..run {
+                            let src_base = (pos + frame) * channels;
+                            let dst_base = (mixed + frame) * self.num_channels;
+                            let g = v.gain.next() * v.mute_gate.next();
+                            for ch in 0..channels.min(self.num_channels) {
+                                buffer[dst_base + ch] += samples[src_base + ch] * g;
+                            }
+                        }
+                        pos += run;
+                        mixed += run;
+                        match loop_region {
+                            Some((loop_start, loop_end)) if pos == loop_end => pos = loop_start,
+                            _ if run == 0 => break,
+                            _ => {}
                         }
                     }
-                    v.playhead += frames_to_mix * channels;
-                    if v.playhead >= samples.len() {
-                        v.active.store(false, Ordering::Release);
-                        v.sfx = None;
-                        v.meta = None;
-                        v.playhead = 0;
+                    v.playhead = pos * channels;
+                    if v.playhead >= samples.len() || v.faded_out() {
+                        v.stop();
                     }
                 }
             }
         }
 
         // stream mixing: reuse preallocated scratch to avoid allocation
-        let scratch_len = num_frames * self.num_channels;
         if self.stream_scratch.len() < scratch_len {
             // grow scratch to accommodate larger backend buffers
             self.stream_scratch.resize(scratch_len, 0.0f32);
@@ -366,7 +723,67 @@ impl Renderer {
             }
         }
 
-        self.api
-            .fill_interleaved_f32(self.num_channels, num_frames, buffer)
+        // Render resonance into its own scratch so it can be trimmed and summed
+        // with the local mix rather than overwriting it.
+        self.output_started = true;
+        if self.resonance_scratch.len() < scratch_len {
+            self.resonance_scratch.resize(scratch_len, 0.0f32);
+        }
+        let spatial = &mut self.resonance_scratch[..scratch_len];
+        let ok = self
+            .api
+            .fill_interleaved_f32(self.num_channels, num_frames, spatial);
+        if ok {
+            for (dst, src) in buffer[..scratch_len]
+                .chunks_exact_mut(self.num_channels)
+                .zip(spatial.chunks_exact(self.num_channels))
+            {
+                let g = self.resonance_gain.next();
+                for (d, s) in dst.iter_mut().zip(src.iter()) {
+                    *d += *s * g;
+                }
+            }
+        }
+        if !(self.master_gain.is_settled() && self.master_gain.current() == 1.0) {
+            for frame in buffer[..scratch_len].chunks_exact_mut(self.num_channels) {
+                let g = self.master_gain.next();
+                for s in frame.iter_mut() {
+                    *s *= g;
+                }
+            }
+        }
+        self.scrub_non_finite(&mut buffer[..expected_len]);
+        self.stream_time_frames += num_frames as u64;
+        ok
+    }
+
+    /// Replace NaN/Inf output samples with silence so bad source data never
+    /// reaches the device. By default only every `NAN_GUARD_STRIDE`-th sample
+    /// is checked (with a rotating offset so all positions are covered over
+    /// successive blocks); a hit triggers a full scrub of the block.
+    fn scrub_non_finite(&mut self, buffer: &mut [f32]) {
+        if !self.nan_guard {
+            return;
+        }
+        if !cfg!(feature = "nan-guard-full") {
+            let offset = self.nan_guard_offset;
+            self.nan_guard_offset = (offset + 1) % NAN_GUARD_STRIDE;
+            let suspect = buffer
+                .iter()
+                .skip(offset)
+                .step_by(NAN_GUARD_STRIDE)
+                .any(|s| !s.is_finite());
+            if !suspect {
+                return;
+            }
+        }
+        let mut found = 0u64;
+        for s in buffer.iter_mut() {
+            if !s.is_finite() {
+                *s = 0.0;
+                found += 1;
+            }
+        }
+        self.non_finite_samples += found;
     }
 }
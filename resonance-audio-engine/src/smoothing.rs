@@ -0,0 +1,118 @@
+/// A linearly smoothed parameter (gain, pan, occlusion, ...).
+///
+/// Holds the current value, the target, and a per-frame step. Setters on the
+/// control side call `set_target`; the RT mix loop calls `next` once per
+/// frame. A ramp of zero frames snaps straight to the target.
+#[derive(Debug, Clone, Copy)]
+pub struct SmoothedParam {
+    current: f32,
+    target: f32,
+    step: f32,
+    remaining: u32,
+}
+
+impl SmoothedParam {
+    pub fn new(value: f32) -> Self {
+        Self {
+            current: value,
+            target: value,
+            step: 0.0,
+            remaining: 0,
+        }
+    }
+
+    /// Jump to `value` immediately, cancelling any ramp in progress.
+    pub fn snap(&mut self, value: f32) {
+        *self = Self::new(value);
+    }
+
+    /// Ramp from the current value to `target` over `ramp_frames` frames.
+    pub fn set_target(&mut self, target: f32, ramp_frames: u32) {
+        if ramp_frames == 0 {
+            self.snap(target);
+            return;
+        }
+        self.target = target;
+        self.remaining = ramp_frames;
+        self.step = (target - self.current) / ramp_frames as f32;
+    }
+
+    /// Advance one frame and return the value to use for it.
+    #[inline]
+    pub fn next(&mut self) -> f32 {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            self.current = if self.remaining == 0 {
+                self.target
+            } else {
+                self.current + self.step
+            };
+        }
+        self.current
+    }
+
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+impl Default for SmoothedParam {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+/// Convert a smoothing time in milliseconds to a frame count at `sample_rate`.
+pub fn ms_to_frames(ms: f32, sample_rate: u32) -> u32 {
+    (ms.max(0.0) * sample_rate as f32 / 1000.0).round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ramps_linearly_and_lands_exactly_on_target() {
+        let mut p = SmoothedParam::new(0.0);
+        p.set_target(1.0, 4);
+        let vals: Vec<f32> = (0..4).map(|_| p.next()).collect();
+        assert_eq!(vals, vec![0.25, 0.5, 0.75, 1.0]);
+        assert!(p.is_settled());
+        assert_eq!(p.next(), 1.0);
+    }
+
+    #[test]
+    fn zero_ramp_snaps() {
+        let mut p = SmoothedParam::new(0.3);
+        p.set_target(0.8, 0);
+        assert!(p.is_settled());
+        assert_eq!(p.next(), 0.8);
+    }
+
+    #[test]
+    fn retarget_mid_ramp_starts_from_current_value() {
+        let mut p = SmoothedParam::new(0.0);
+        p.set_target(1.0, 10);
+        for _ in 0..5 {
+            p.next();
+        }
+        let mid = p.current();
+        p.set_target(0.0, 2);
+        assert!((p.next() - mid / 2.0).abs() < 1e-6);
+        assert_eq!(p.next(), 0.0);
+    }
+
+    #[test]
+    fn ms_to_frames_rounds() {
+        assert_eq!(ms_to_frames(5.0, 48000), 240);
+        assert_eq!(ms_to_frames(-1.0, 48000), 0);
+    }
+}
@@ -1,8 +1,20 @@
 use resonance_cxx::{Api, DistanceRolloffModel, RenderingMode};
 
+// Distance range used by `set_distance_rolloff` until `set_distance_range`
+// overrides it.
+const DEFAULT_MIN_DISTANCE: f32 = 1.0;
+const DEFAULT_MAX_DISTANCE: f32 = 100.0;
+// Resonance accepts near-field effect gains in [0, 9].
+const MAX_NEAR_FIELD_GAIN: f32 = 9.0;
+
 pub struct Spatializer<'a> {
     api: &'a mut Api,
     source_id: i32,
+    min_distance: f32,
+    max_distance: f32,
+    rolloff: Option<DistanceRolloffModel>,
+    near_field_gain: f32,
+    spread_deg: f32,
 }
 
 impl<'a> Spatializer<'a> {
@@ -13,13 +25,56 @@ impl<'a> Spatializer<'a> {
         Self {
             api,
             source_id: src_id,
+            min_distance: DEFAULT_MIN_DISTANCE,
+            max_distance: DEFAULT_MAX_DISTANCE,
+            rolloff: None,
+            near_field_gain: 0.0,
+            spread_deg: 0.0,
         }
     }
 
-    /// Feed interleaved audio for this source (read-only).
-    pub fn feed_interleaved(&mut self, audio: &[f32], num_channels: usize, num_frames: usize) {
+    /// Create an ambisonic source with `num_channels` (4 for first order, 9
+    /// for second, 16 for third). Returns `None` if resonance rejects the
+    /// channel count. Feed it interleaved ACN/SN3D audio with
+    /// `feed_interleaved`; distance and spread setters have no effect on
+    /// ambisonic sources, but `set_pose` still rotates the sound field.
+    pub fn new_ambisonic(
+        renderer: &'a mut crate::renderer::Renderer,
+        num_channels: usize,
+    ) -> Option<Self> {
+        let api = renderer.api_mut();
+        let src_id = api.create_ambisonic_source(num_channels);
+        if src_id < 0 {
+            return None;
+        }
+        Some(Self {
+            api,
+            source_id: src_id,
+            min_distance: DEFAULT_MIN_DISTANCE,
+            max_distance: DEFAULT_MAX_DISTANCE,
+            rolloff: None,
+            near_field_gain: 0.0,
+            spread_deg: 0.0,
+        })
+    }
+
+    /// Native resonance source id backing this spatializer, as an escape hatch
+    /// for `Api` setters not wrapped here. Destroying the source out-of-band
+    /// leaves this `Spatializer` pointing at a dead id; use `destroy()` instead.
+    pub fn source_id(&self) -> i32 {
+        self.source_id
+    }
+
+    /// Feed interleaved audio for this source (read-only). Returns false if
+    /// `audio.len()` does not match `num_channels * num_frames`.
+    pub fn feed_interleaved(
+        &mut self,
+        audio: &[f32],
+        num_channels: usize,
+        num_frames: usize,
+    ) -> bool {
         self.api
-            .set_interleaved_buffer_f32(self.source_id, audio, num_channels, num_frames);
+            .set_interleaved_buffer_f32(self.source_id, audio, num_channels, num_frames)
     }
 
     /// Feed planar audio (borrowed slices per channel).
@@ -33,9 +88,39 @@ impl<'a> Spatializer<'a> {
     }
 
     pub fn set_distance_rolloff(&mut self, model: DistanceRolloffModel) {
-        // map to resonance-cxx distance model type
-        self.api
-            .set_source_distance_model(self.source_id, model, 1.0, 100.0);
+        self.rolloff = Some(model);
+        self.api.set_source_distance_model(
+            self.source_id,
+            model,
+            self.min_distance,
+            self.max_distance,
+        );
+    }
+
+    /// Set the distance range used by the rolloff model (defaults 1.0 / 100.0).
+    /// Re-applies the current model if one has been set. Values are sanitized
+    /// so that `0 <= min <= max`.
+    pub fn set_distance_range(&mut self, min_distance: f32, max_distance: f32) {
+        let min = if min_distance.is_finite() {
+            min_distance.max(0.0)
+        } else {
+            DEFAULT_MIN_DISTANCE
+        };
+        let max = if max_distance.is_finite() {
+            max_distance.max(min)
+        } else {
+            DEFAULT_MAX_DISTANCE.max(min)
+        };
+        self.min_distance = min;
+        self.max_distance = max;
+        if let Some(model) = self.rolloff {
+            self.set_distance_rolloff(model);
+        }
+    }
+
+    /// Current `(min_distance, max_distance)`.
+    pub fn distance_range(&self) -> (f32, f32) {
+        (self.min_distance, self.max_distance)
     }
 
     /// Set source spatial pose (position + rotation quaternion).
@@ -56,6 +141,48 @@ impl<'a> Spatializer<'a> {
             .set_source_distance_attenuation(self.source_id, attenuation);
     }
 
+    /// Set occlusion intensity (0 = unoccluded; maps to SetSoundObjectOcclusionIntensity).
+    pub fn set_occlusion(&mut self, intensity: f32) {
+        self.api
+            .set_sound_object_occlusion_intensity(self.source_id, intensity.max(0.0));
+    }
+
+    /// Set source spread (width) in degrees (maps to SetSoundObjectSpread).
+    /// Clamped to `[0, 360]`; NaN collapses to a point source.
+    pub fn set_spread(&mut self, spread_deg: f32) {
+        let spread = if spread_deg.is_nan() {
+            0.0
+        } else {
+            spread_deg.clamp(0.0, 360.0)
+        };
+        self.spread_deg = spread;
+        self.api.set_sound_object_spread(self.source_id, spread);
+    }
+
+    /// Spread last applied, in degrees (0.0 until set).
+    pub fn spread(&self) -> f32 {
+        self.spread_deg
+    }
+
+    /// Set the near-field effect gain (proximity boost for sources closer
+    /// than ~1 m; maps to SetSoundObjectNearFieldEffectGain). Clamped to
+    /// `[0, 9]`; NaN disables the effect.
+    pub fn set_near_field_gain(&mut self, gain: f32) {
+        let gain = if gain.is_nan() {
+            0.0
+        } else {
+            gain.clamp(0.0, MAX_NEAR_FIELD_GAIN)
+        };
+        self.near_field_gain = gain;
+        self.api
+            .set_sound_object_near_field_effect_gain(self.source_id, gain);
+    }
+
+    /// Near-field effect gain last applied (0.0 until set).
+    pub fn near_field_gain(&self) -> f32 {
+        self.near_field_gain
+    }
+
     pub fn destroy(self) {
         let id = self.source_id;
         self.api.destroy_source(id);
@@ -1,5 +1,5 @@
 use resonance_audio_engine::Renderer;
-use resonance_audio_engine::renderer::{Command, SfxBuffer};
+use resonance_audio_engine::renderer::{Command, SfxBuffer, SourceGenerator};
 use asset_manager::sfx_loader::SfxMetadata;
 use std::sync::Arc;
 use std::thread;
@@ -130,3 +130,293 @@ fn concurrency_stress_short_run() {
     }
     let _ = handle.join();
 }
+
+#[test]
+fn non_finite_samples_are_replaced_with_silence() {
+    let mut r = Renderer::new(48000, 2, 32);
+    let sender = r.command_sender();
+    let slot = r.alloc_slot().expect("slot");
+    sender.push(Command::CreateSource { slot, mode: resonance_cxx::RenderingMode::kStereoPanning }).ok();
+
+    let samples = Arc::new(vec![f32::NAN; 32 * 2]);
+    let meta = SfxMetadata { channels: 2, sample_rate: 48000, loop_points: None };
+    let sfx = SfxBuffer { samples, meta };
+    sender.push(Command::PlaySfx { slot, buffer: sfx, gain: 1.0, pos: None }).ok();
+
+    let mut out = vec![0.0f32; 32 * 2];
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert!(out.iter().all(|v| v.is_finite()), "output must not contain NaN/Inf");
+    assert!(r.debug_non_finite_count() > 0, "scrubbed samples should be counted");
+}
+
+struct ConstGenerator(f32);
+
+impl SourceGenerator for ConstGenerator {
+    fn render(&self, out: &mut [f32], _sample_rate: u32) {
+        out.iter_mut().for_each(|s| *s = self.0);
+    }
+}
+
+#[test]
+fn custom_generator_is_mixed_with_voice_gain_until_stopped() {
+    let mut r = Renderer::new(48000, 2, 32);
+    let sender = r.command_sender();
+    let slot = r.alloc_slot().expect("slot");
+    sender.push(Command::PlayGenerator { slot, generator: Arc::new(ConstGenerator(0.5)), gain: 0.5, pos: None }).ok();
+
+    let mut out = vec![0.0f32; 32 * 2];
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert!(out.iter().all(|v| (*v - 0.25).abs() < 1e-6), "generator output should be scaled by gain");
+    assert_ne!(r.alloc_slot(), Some(slot), "generator slot should stay allocated while playing");
+
+    sender.push(Command::StopVoice { slot }).ok();
+    let mut out2 = vec![0.0f32; 32 * 2];
+    let _ = r.process_output_interleaved(&mut out2, 32);
+    assert!(out2.iter().all(|v| *v == 0.0), "stopped generator should be silent");
+}
+
+#[test]
+fn gain_change_on_playing_voice_is_ramped() {
+    let mut r = Renderer::new(48000, 2, 256);
+    r.set_gain_smoothing_ms(5.0); // 240 frames at 48 kHz
+    let sender = r.command_sender();
+    let slot = r.alloc_slot().expect("slot");
+    sender.push(Command::PlayGenerator { slot, generator: Arc::new(ConstGenerator(1.0)), gain: 1.0, pos: None }).ok();
+
+    let mut out = vec![0.0f32; 256 * 2];
+    let _ = r.process_output_interleaved(&mut out, 256);
+
+    sender.push(Command::SetVoiceGain { slot, gain: 0.0 }).ok();
+    let _ = r.process_output_interleaved(&mut out, 256);
+    assert!(out[0] > 0.9, "ramp should start near the previous gain, got {}", out[0]);
+    assert!(out[0] < 1.0, "ramp should already be moving on the first frame");
+    assert_eq!(out[255 * 2], 0.0, "ramp should have reached the target by the end of the block");
+}
+
+#[test]
+fn nan_voice_gain_is_sanitized_at_the_command_boundary() {
+    let mut r = Renderer::new(48000, 2, 32);
+    let sender = r.command_sender();
+    let slot = r.alloc_slot().expect("slot");
+    sender.push(Command::PlayGenerator { slot, generator: Arc::new(ConstGenerator(0.5)), gain: f32::NAN, pos: None }).ok();
+
+    let mut out = vec![0.0f32; 32 * 2];
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert!(out.iter().all(|v| v.is_finite()), "NaN gain must not reach the output");
+    assert_eq!(r.debug_non_finite_count(), 0, "gain should be sanitized before mixing, not scrubbed after");
+
+    sender.push(Command::SetVoiceGain { slot, gain: f32::INFINITY }).ok();
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert!(out.iter().all(|v| v.is_finite()), "infinite gain must be clamped");
+}
+
+#[test]
+fn mute_then_unmute_restores_previous_level() {
+    let mut r = Renderer::new(48000, 2, 32);
+    r.set_gain_smoothing_ms(0.0);
+    let sender = r.command_sender();
+    let slot = r.alloc_slot().expect("slot");
+    sender.push(Command::PlayGenerator { slot, generator: Arc::new(ConstGenerator(1.0)), gain: 0.3, pos: None }).ok();
+
+    let mut out = vec![0.0f32; 32 * 2];
+    let _ = r.process_output_interleaved(&mut out, 32);
+    let before = out[0];
+
+    sender.push(Command::SetVoiceMuted { slot, muted: true }).ok();
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert!(out.iter().all(|v| *v == 0.0), "muted voice should be silent");
+
+    sender.push(Command::SetVoiceMuted { slot, muted: false }).ok();
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert_eq!(out[0], before, "unmuting should restore the exact prior level");
+}
+
+#[test]
+fn resonance_fill_is_trimmed_and_summed_with_local_mix() {
+    // Local voice at 0.25 plus a loud natively-spatialized source, rendered
+    // with the given resonance trim.
+    let render = |trim_db: Option<f32>| {
+        let mut r = Renderer::new(48000, 2, 64);
+        match trim_db {
+            Some(db) => r.set_resonance_gain_db(db),
+            None => r.set_resonance_gain(0.0),
+        }
+        {
+            let mut sp = resonance_audio_engine::Spatializer::new(&mut r, resonance_cxx::RenderingMode::kStereoPanning);
+            sp.feed_interleaved(&[0.9f32; 64], 1, 64);
+        }
+        let sender = r.command_sender();
+        let slot = r.alloc_slot().expect("slot");
+        sender.push(Command::PlayGenerator { slot, generator: Arc::new(ConstGenerator(0.25)), gain: 1.0, pos: None }).ok();
+        let mut out = vec![0.0f32; 64 * 2];
+        let _ = r.process_output_interleaved(&mut out, 64);
+        out
+    };
+
+    let muted = render(None);
+    assert!(muted.iter().all(|v| (*v - 0.25).abs() < 1e-6), "local mix must survive the resonance fill; a zero trim removes the spatial bed");
+
+    let full = render(Some(0.0));
+    let trimmed = render(Some(-3.0));
+    let expected = 10f32.powf(-3.0 / 20.0);
+    let mut compared = 0;
+    for (f, t) in full.iter().zip(&trimmed) {
+        let (res_full, res_trimmed) = (f - 0.25, t - 0.25);
+        if res_full.abs() > 1e-3 {
+            assert!((res_trimmed / res_full - expected).abs() < 1e-3, "resonance part should scale by {}, got {} -> {}", expected, res_full, res_trimmed);
+            compared += 1;
+        }
+    }
+    assert!(compared > 0, "the resonance source must actually contribute to the mix");
+}
+
+#[test]
+fn paused_voice_is_silent_and_resumes_where_it_left_off() {
+    let mut r = Renderer::new(48000, 2, 32);
+    let sender = r.command_sender();
+    let slot = r.alloc_slot().expect("slot");
+    // frame k holds k/1000 on both channels so the playhead is observable
+    let samples: Vec<f32> = (0..256).flat_map(|k| [k as f32 / 1000.0; 2]).collect();
+    let meta = SfxMetadata { channels: 2, sample_rate: 48000, loop_points: None };
+    sender.push(Command::PlaySfx { slot, buffer: SfxBuffer { samples: Arc::new(samples), meta }, gain: 1.0, pos: None }).ok();
+
+    let mut out = vec![0.0f32; 32 * 2];
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert!((out[62] - 0.031).abs() < 1e-6);
+    let remaining_before = r.debug_voice_info(slot).unwrap().1;
+
+    sender.push(Command::PauseVoice { slot }).ok();
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert!(out.iter().all(|v| *v == 0.0), "paused voice must be silent");
+    assert_eq!(r.debug_voice_info(slot), Some((true, remaining_before)), "pause must not advance or release the voice");
+    assert_ne!(r.alloc_slot(), Some(slot), "paused slot stays allocated");
+
+    sender.push(Command::ResumeVoice { slot }).ok();
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert!((out[0] - 0.032).abs() < 1e-6, "resume continues from the saved playhead, got {}", out[0]);
+    assert!((out[62] - 0.063).abs() < 1e-6);
+}
+
+#[test]
+fn master_gain_scales_the_final_mix_and_is_clamped() {
+    let render_peak = |master: Option<f32>| {
+        let mut r = Renderer::new(48000, 2, 32);
+        let sender = r.command_sender();
+        if let Some(gain) = master {
+            sender.push(Command::SetMasterGain { gain }).ok();
+        }
+        let slot = r.alloc_slot().expect("slot");
+        sender.push(Command::PlayGenerator { slot, generator: Arc::new(ConstGenerator(0.8)), gain: 1.0, pos: None }).ok();
+        let mut out = vec![0.0f32; 32 * 2];
+        let _ = r.process_output_interleaved(&mut out, 32);
+        out.iter().fold(0.0f32, |m, v| m.max(v.abs()))
+    };
+
+    let full = render_peak(None);
+    assert!((full - 0.8).abs() < 1e-6);
+    assert!((render_peak(Some(0.5)) - full * 0.5).abs() < 1e-6, "master 0.5 halves the peak");
+    assert!((render_peak(Some(3.0)) - full).abs() < 1e-6, "master gain is clamped to 1.0");
+    assert_eq!(render_peak(Some(f32::NAN)), 0.0);
+}
+
+#[test]
+fn looped_sfx_wraps_at_loop_points_while_one_shot_stops() {
+    let ramp = |frames: usize| -> Arc<Vec<f32>> { Arc::new((0..frames).flat_map(|k| [k as f32 / 1000.0; 2]).collect()) };
+    let mut r = Renderer::new(48000, 2, 32);
+    let sender = r.command_sender();
+
+    let one_shot = r.alloc_slot().expect("slot");
+    let meta = SfxMetadata { channels: 2, sample_rate: 48000, loop_points: None };
+    sender.push(Command::PlaySfx { slot: one_shot, buffer: SfxBuffer { samples: ramp(48), meta }, gain: 1.0, pos: None }).ok();
+    let mut out = vec![0.0f32; 32 * 2];
+    let _ = r.process_output_interleaved(&mut out, 32);
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert!(out[32..].iter().all(|v| *v == 0.0), "one-shot runs out after 48 frames");
+    assert_eq!(r.debug_voice_info(one_shot).map(|i| i.0), Some(false));
+
+    let looped = r.alloc_slot().expect("slot");
+    let meta = SfxMetadata { channels: 2, sample_rate: 48000, loop_points: Some((16, 48)) };
+    sender.push(Command::PlaySfx { slot: looped, buffer: SfxBuffer { samples: ramp(64), meta }, gain: 1.0, pos: None }).ok();
+    let _ = r.process_output_interleaved(&mut out, 32); // frames 0..32
+    let _ = r.process_output_interleaved(&mut out, 32); // frames 32..48 then 16..32
+    assert!((out[15 * 2] - 0.047).abs() < 1e-6, "last frame before the loop end");
+    assert!((out[16 * 2] - 0.016).abs() < 1e-6, "wrapped back to the loop start, got {}", out[16 * 2]);
+    for _ in 0..8 {
+        let _ = r.process_output_interleaved(&mut out, 32);
+        assert!(out.iter().any(|v| *v != 0.0), "looped voice keeps producing output");
+    }
+    assert_eq!(r.debug_voice_info(looped).map(|i| i.0), Some(true));
+}
+
+#[test]
+fn fade_out_keeps_voice_until_silent_then_stops_it() {
+    let mut r = Renderer::new(48000, 2, 32);
+    let sender = r.command_sender();
+    let slot = r.alloc_slot().expect("slot");
+    sender.push(Command::PlayGenerator { slot, generator: Arc::new(ConstGenerator(1.0)), gain: 1.0, pos: None }).ok();
+    let mut out = vec![0.0f32; 32 * 2];
+    let _ = r.process_output_interleaved(&mut out, 32);
+
+    // 1 ms at 48 kHz = 48 frames, so the fade spans one and a half blocks.
+    sender.push(Command::FadeOutVoice { slot, fade_ms: 1.0 }).ok();
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert_eq!(r.debug_voice_info(slot).map(|i| i.0), Some(true), "voice is still present mid-fade");
+    assert!(out[0] > 0.9 && out[31 * 2] < out[0], "gain should be ramping down");
+
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert_eq!(r.debug_voice_info(slot).map(|i| i.0), Some(false), "voice is released once the fade completes");
+    let _ = r.process_output_interleaved(&mut out, 32);
+    assert!(out.iter().all(|v| *v == 0.0));
+    assert_eq!(r.alloc_slot(), Some(slot));
+}
+
+#[test]
+fn scheduled_sfx_starts_on_its_stream_frame() {
+    let mut r = Renderer::new(48000, 2, 64);
+    let sender = r.command_sender();
+    let slot = r.alloc_slot().expect("slot");
+    let meta = SfxMetadata { channels: 2, sample_rate: 48000, loop_points: None };
+    let samples = Arc::new(vec![0.5f32; 256 * 2]);
+    let start_frame = r.stream_time_frames() + 128;
+    sender.push(Command::PlaySfxAt { slot, buffer: SfxBuffer { samples, meta }, gain: 1.0, pos: None, start_frame }).ok();
+
+    let mut out = vec![0.0f32; 64 * 2];
+    for _ in 0..2 {
+        let _ = r.process_output_interleaved(&mut out, 64);
+        assert!(out.iter().all(|v| *v == 0.0), "voice must wait for its start frame");
+    }
+    assert_eq!(r.stream_time_frames(), 128);
+    assert_eq!(r.debug_voice_info(slot), Some((true, 256)), "waiting voice keeps its playhead at zero");
+
+    let _ = r.process_output_interleaved(&mut out, 64);
+    assert_eq!(out[0], 0.5, "voice starts on the first frame of the third block");
+    assert!((r.stream_time_secs() - 192.0 / 48000.0).abs() < 1e-12);
+
+    // A start inside a block is frame-accurate.
+    let slot2 = r.alloc_slot().expect("slot");
+    let meta = SfxMetadata { channels: 2, sample_rate: 48000, loop_points: None };
+    let start_frame = r.stream_time_frames() + 10;
+    sender.push(Command::StopVoice { slot }).ok();
+    sender.push(Command::PlaySfxAt { slot: slot2, buffer: SfxBuffer { samples: Arc::new(vec![0.25f32; 64 * 2]), meta }, gain: 1.0, pos: None, start_frame }).ok();
+    let _ = r.process_output_interleaved(&mut out, 64);
+    assert_eq!(out[9 * 2], 0.0);
+    assert_eq!(out[10 * 2], 0.25);
+}
+
+#[test]
+fn resonance_gain_command_reaches_a_running_renderer() {
+    let mut r = Renderer::new(48000, 2, 64);
+    r.set_gain_smoothing_ms(0.0);
+    let sender = r.command_sender();
+    let mut out = vec![0.0f32; 64 * 2];
+    let _ = r.process_output_interleaved(&mut out, 64);
+
+    // Output has started, so only the queue can reach the renderer now.
+    sender.push(Command::SetResonanceGain { gain: 0.0 }).ok();
+    {
+        let mut sp = resonance_audio_engine::Spatializer::new(&mut r, resonance_cxx::RenderingMode::kStereoPanning);
+        sp.feed_interleaved(&[0.9f32; 64], 1, 64);
+    }
+    let _ = r.process_output_interleaved(&mut out, 64);
+    assert!(out.iter().all(|v| *v == 0.0), "a zero trim from the queue silences the resonance bed");
+}
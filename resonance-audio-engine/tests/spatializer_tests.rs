@@ -23,7 +23,86 @@ fn spatializer_create_feed_and_destroy() {
     s.set_pose(1.0, 2.0, 3.0, 0.0, 0.0, 0.0, 1.0);
     s.set_room_effects_gain(0.5);
     s.set_distance_attenuation(2.0);
+    s.set_occlusion(0.5);
+    s.set_spread(45.0);
 
     // destroy should call Api destroy
     s.destroy();
 }
+
+#[test]
+fn spatializer_rejects_mismatched_interleaved_feed() {
+    let mut r = Renderer::new(48000, 2, 64);
+    let mut s = Spatializer::new(&mut r, resonance_cxx::RenderingMode::kStereoPanning);
+    assert!(!s.feed_interleaved(&[0.1f32; 100], 2, 64), "short buffer must not reach the FFI");
+    assert!(s.feed_interleaved(&[0.1f32; 128], 2, 64));
+    s.destroy();
+}
+
+#[test]
+fn spatializer_exposes_native_source_id() {
+    let mut r = Renderer::new(48000, 2, 64);
+    let s = Spatializer::new(&mut r, resonance_cxx::RenderingMode::kStereoPanning);
+    let id = s.source_id();
+    assert!(id >= 0, "expected a valid native source id, got {}", id);
+    s.destroy();
+}
+
+#[test]
+fn spatializer_distance_range_defaults_and_overrides() {
+    let mut r = Renderer::new(48000, 2, 64);
+    let mut s = Spatializer::new(&mut r, resonance_cxx::RenderingMode::kStereoPanning);
+    assert_eq!(s.distance_range(), (1.0, 100.0));
+
+    s.set_distance_rolloff(resonance_cxx::DistanceRolloffModel::kLogarithmic);
+    s.set_distance_range(2.0, 500.0);
+    assert_eq!(s.distance_range(), (2.0, 500.0));
+
+    // inverted / non-finite input is sanitized rather than forwarded
+    s.set_distance_range(10.0, 5.0);
+    assert_eq!(s.distance_range(), (10.0, 10.0));
+    s.set_distance_range(f32::NAN, f32::INFINITY);
+    assert_eq!(s.distance_range(), (1.0, 100.0));
+    s.destroy();
+}
+
+#[test]
+fn spatializer_near_field_gain_is_clamped() {
+    let mut r = Renderer::new(48000, 2, 64);
+    let mut s = Spatializer::new(&mut r, resonance_cxx::RenderingMode::kBinauralHighQuality);
+    assert_eq!(s.near_field_gain(), 0.0);
+    s.set_near_field_gain(2.5);
+    assert_eq!(s.near_field_gain(), 2.5);
+    s.set_near_field_gain(50.0);
+    assert_eq!(s.near_field_gain(), 9.0);
+    s.set_near_field_gain(f32::NAN);
+    assert_eq!(s.near_field_gain(), 0.0);
+    s.destroy();
+}
+
+#[test]
+fn ambisonic_spatializer_accepts_four_channel_feed() {
+    let mut r = Renderer::new(48000, 2, 64);
+    let Some(mut s) = Spatializer::new_ambisonic(&mut r, 4) else {
+        eprintln!("skipping: ambisonic source creation not supported");
+        return;
+    };
+    assert!(s.source_id() >= 0);
+    assert!(s.feed_interleaved(&[0.1f32; 4 * 64], 4, 64), "first-order feed should be accepted");
+    assert!(!s.feed_interleaved(&[0.1f32; 2 * 64], 4, 64), "short feed must be rejected");
+    s.set_pose(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
+    s.destroy();
+}
+
+#[test]
+fn spatializer_spread_is_clamped_and_nan_safe() {
+    let mut r = Renderer::new(48000, 2, 64);
+    let mut s = Spatializer::new(&mut r, resonance_cxx::RenderingMode::kStereoPanning);
+    s.set_spread(45.0);
+    assert_eq!(s.spread(), 45.0);
+    s.set_spread(720.0);
+    assert_eq!(s.spread(), 360.0);
+    s.set_spread(f32::NAN);
+    assert_eq!(s.spread(), 0.0, "NaN must not reach the FFI");
+    s.destroy();
+}
@@ -1,10 +1,12 @@
 pub mod bridge;
+pub mod interleave;
 
 pub use bridge::{
     DistanceRolloffModel, ReflectionProperties, RenderingMode, ResonanceAudioApi, ReverbProperties,
 };
 
 use cxx::UniquePtr;
+use std::fmt;
 use std::pin::Pin;
 
 // Safety: The upstream C++ `vraudio::ResonanceAudioApi` is documented in
@@ -17,6 +19,87 @@ use std::pin::Pin;
 unsafe impl Send for bridge::ResonanceAudioApi {}
 unsafe impl Sync for bridge::ResonanceAudioApi {}
 
+/// Errors reported by the checked (`try_*`) variants of the `Api` helpers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResonanceError {
+    /// An empty channel slice was passed where audio was expected. The plain
+    /// `bool` helpers treat this as a no-op; the checked variants surface it
+    /// because it is usually a caller forgetting to populate the channels.
+    NoChannels,
+    /// A planar channel's length does not match the requested frame count.
+    FrameCountMismatch {
+        channel: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// An interleaved buffer's length is not `num_channels * num_frames`.
+    /// Rejected before the FFI call, since the C++ side trusts the counts.
+    BufferLengthMismatch { expected: usize, actual: usize },
+}
+
+impl fmt::Display for ResonanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResonanceError::NoChannels => write!(f, "no channels supplied"),
+            ResonanceError::FrameCountMismatch {
+                channel,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "channel {} has {} frames, expected {}",
+                channel, actual, expected
+            ),
+            ResonanceError::BufferLengthMismatch { expected, actual } => write!(
+                f,
+                "interleaved buffer has {} samples, expected {}",
+                actual, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ResonanceError {}
+
+/// Validate that every planar channel holds exactly `num_frames` samples.
+fn check_planar<T>(channels: &[&[T]], num_frames: usize) -> Result<(), ResonanceError> {
+    if channels.is_empty() {
+        return Err(ResonanceError::NoChannels);
+    }
+    for (channel, ch) in channels.iter().enumerate() {
+        if ch.len() != num_frames {
+            return Err(ResonanceError::FrameCountMismatch {
+                channel,
+                expected: num_frames,
+                actual: ch.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Validate an interleaved buffer against its declared shape.
+fn check_interleaved<T>(
+    audio: &[T],
+    num_channels: usize,
+    num_frames: usize,
+) -> Result<(), ResonanceError> {
+    let expected = num_channels.saturating_mul(num_frames);
+    if audio.len() != expected {
+        return Err(ResonanceError::BufferLengthMismatch {
+            expected,
+            actual: audio.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Collapse a checked result into the legacy `bool` convention, where an
+/// empty channel list is an intentional no-op.
+fn planar_ok(result: Result<(), ResonanceError>) -> bool {
+    matches!(result, Ok(()) | Err(ResonanceError::NoChannels))
+}
+
 /// Safe, ergonomic owner for the underlying C++ `ResonanceAudioApi`.
 pub struct Api {
     inner: UniquePtr<ResonanceAudioApi>,
@@ -92,46 +175,44 @@ impl Api {
         }
 
         // Deinterleave into planar slices
-        for frame in 0..num_frames {
-            for ch in 0..num_channels {
-                channels[ch][frame] = interleaved[frame * num_channels + ch];
-            }
-        }
+        interleave::deinterleave(&interleaved, channels, num_frames);
         true
     }
 
     /// Set a planar source buffer (immutable) by interleaving into a temporary
     /// and calling the interleaved setter. This avoids exposing raw pointers
     /// across the FFI and keeps the public surface safe.
+    ///
+    /// Returns true for an empty `channels` slice; use
+    /// `try_set_planar_buffer_f32` to have that reported as an error.
     pub fn set_planar_buffer_f32(
         &mut self,
         source_id: i32,
         channels: &[&[f32]],
         num_frames: usize,
     ) -> bool {
-        if channels.is_empty() {
-            return true;
-        }
-        let num_channels = channels.len();
-        for ch in channels.iter() {
-            if ch.len() != num_frames {
-                return false;
-            }
-        }
+        planar_ok(self.try_set_planar_buffer_f32(source_id, channels, num_frames))
+    }
 
+    /// Checked variant of `set_planar_buffer_f32`: rejects an empty channel
+    /// list and reports which channel disagrees with `num_frames`.
+    pub fn try_set_planar_buffer_f32(
+        &mut self,
+        source_id: i32,
+        channels: &[&[f32]],
+        num_frames: usize,
+    ) -> Result<(), ResonanceError> {
+        check_planar(channels, num_frames)?;
+        let num_channels = channels.len();
         let mut interleaved = vec![0f32; num_channels * num_frames];
-        for frame in 0..num_frames {
-            for ch in 0..num_channels {
-                interleaved[frame * num_channels + ch] = channels[ch][frame];
-            }
-        }
+        interleave::interleave(channels, num_frames, &mut interleaved);
         self.as_pin_mut().set_interleaved_buffer_f32(
             source_id,
             &interleaved,
             num_channels,
             num_frames,
         );
-        true
+        Ok(())
     }
 
     /// Planar helpers for i16 audio.
@@ -156,11 +237,7 @@ impl Api {
         if !ok {
             return false;
         }
-        for frame in 0..num_frames {
-            for ch in 0..num_channels {
-                channels[ch][frame] = interleaved[frame * num_channels + ch];
-            }
-        }
+        interleave::deinterleave(&interleaved, channels, num_frames);
         true
     }
 
@@ -170,28 +247,27 @@ impl Api {
         channels: &[&[i16]],
         num_frames: usize,
     ) -> bool {
-        if channels.is_empty() {
-            return true;
-        }
+        planar_ok(self.try_set_planar_buffer_i16(source_id, channels, num_frames))
+    }
+
+    /// Checked variant of `set_planar_buffer_i16`.
+    pub fn try_set_planar_buffer_i16(
+        &mut self,
+        source_id: i32,
+        channels: &[&[i16]],
+        num_frames: usize,
+    ) -> Result<(), ResonanceError> {
+        check_planar(channels, num_frames)?;
         let num_channels = channels.len();
-        for ch in channels.iter() {
-            if ch.len() != num_frames {
-                return false;
-            }
-        }
         let mut interleaved = vec![0i16; num_channels * num_frames];
-        for frame in 0..num_frames {
-            for ch in 0..num_channels {
-                interleaved[frame * num_channels + ch] = channels[ch][frame];
-            }
-        }
+        interleave::interleave(channels, num_frames, &mut interleaved);
         self.as_pin_mut().set_interleaved_buffer_i16(
             source_id,
             &interleaved,
             num_channels,
             num_frames,
         );
-        true
+        Ok(())
     }
 
     /// Variant that accepts a caller-provided interleaved scratch buffer for f32.
@@ -204,26 +280,17 @@ impl Api {
         num_frames: usize,
         scratch: &mut Vec<f32>,
     ) -> bool {
-        if channels.is_empty() {
-            return true;
+        if let Err(e) = check_planar(channels, num_frames) {
+            return planar_ok(Err(e));
         }
         let num_channels = channels.len();
-        for ch in channels.iter() {
-            if ch.len() != num_frames {
-                return false;
-            }
-        }
         let needed = num_channels * num_frames;
         if scratch.len() < needed {
             scratch.resize(needed, 0.0);
         }
-        for frame in 0..num_frames {
-            for ch in 0..num_channels {
-                scratch[frame * num_channels + ch] = channels[ch][frame];
-            }
-        }
+        interleave::interleave(channels, num_frames, scratch);
         self.as_pin_mut()
-            .set_interleaved_buffer_f32(source_id, scratch, num_channels, num_frames);
+            .set_interleaved_buffer_f32(source_id, &scratch[..needed], num_channels, num_frames);
         true
     }
 
@@ -234,26 +301,17 @@ impl Api {
         num_frames: usize,
         scratch: &mut Vec<i16>,
     ) -> bool {
-        if channels.is_empty() {
-            return true;
+        if let Err(e) = check_planar(channels, num_frames) {
+            return planar_ok(Err(e));
         }
         let num_channels = channels.len();
-        for ch in channels.iter() {
-            if ch.len() != num_frames {
-                return false;
-            }
-        }
         let needed = num_channels * num_frames;
         if scratch.len() < needed {
             scratch.resize(needed, 0);
         }
-        for frame in 0..num_frames {
-            for ch in 0..num_channels {
-                scratch[frame * num_channels + ch] = channels[ch][frame];
-            }
-        }
+        interleave::interleave(channels, num_frames, scratch);
         self.as_pin_mut()
-            .set_interleaved_buffer_i16(source_id, scratch, num_channels, num_frames);
+            .set_interleaved_buffer_i16(source_id, &scratch[..needed], num_channels, num_frames);
         true
     }
 
@@ -283,15 +341,30 @@ impl Api {
         self.as_pin_mut().destroy_source(id);
     }
 
+    /// Feed interleaved audio to a source. Returns false (and skips the FFI
+    /// call) when `audio.len() != num_channels * num_frames`.
     pub fn set_interleaved_buffer_f32(
         &mut self,
         source_id: i32,
         audio: &[f32],
         num_channels: usize,
         num_frames: usize,
-    ) {
+    ) -> bool {
+        self.try_set_interleaved_buffer_f32(source_id, audio, num_channels, num_frames)
+            .is_ok()
+    }
+
+    pub fn try_set_interleaved_buffer_f32(
+        &mut self,
+        source_id: i32,
+        audio: &[f32],
+        num_channels: usize,
+        num_frames: usize,
+    ) -> Result<(), ResonanceError> {
+        check_interleaved(audio, num_channels, num_frames)?;
         self.as_pin_mut()
             .set_interleaved_buffer_f32(source_id, audio, num_channels, num_frames);
+        Ok(())
     }
 
     pub fn set_interleaved_buffer_i16(
@@ -300,9 +373,22 @@ impl Api {
         audio: &[i16],
         num_channels: usize,
         num_frames: usize,
-    ) {
+    ) -> bool {
+        self.try_set_interleaved_buffer_i16(source_id, audio, num_channels, num_frames)
+            .is_ok()
+    }
+
+    pub fn try_set_interleaved_buffer_i16(
+        &mut self,
+        source_id: i32,
+        audio: &[i16],
+        num_channels: usize,
+        num_frames: usize,
+    ) -> Result<(), ResonanceError> {
+        check_interleaved(audio, num_channels, num_frames)?;
         self.as_pin_mut()
             .set_interleaved_buffer_i16(source_id, audio, num_channels, num_frames);
+        Ok(())
     }
 
     pub fn set_source_distance_attenuation(&mut self, source_id: i32, distance_attenuation: f32) {
@@ -0,0 +1,99 @@
+use resonance_cxx::interleave::{deinterleave, deinterleave_scalar, interleave, interleave_scalar};
+use std::time::Instant;
+
+fn planar_f32(num_channels: usize, num_frames: usize) -> Vec<Vec<f32>> {
+    (0..num_channels)
+        .map(|ch| {
+            (0..num_frames)
+                .map(|f| ((ch * 7919 + f * 31) % 1000) as f32 / 997.0 - 0.5)
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn interleave_matches_scalar_bit_for_bit() {
+    for num_channels in [1usize, 2, 3, 4, 6, 8] {
+        let num_frames = 257;
+        let mut planar = planar_f32(num_channels, num_frames);
+        // Odd bit patterns must survive the copy untouched.
+        planar[0][3] = f32::NAN;
+        planar[num_channels - 1][5] = -0.0;
+        let refs: Vec<&[f32]> = planar.iter().map(|v| v.as_slice()).collect();
+
+        let mut fast = vec![0f32; num_channels * num_frames];
+        let mut scalar = vec![0f32; num_channels * num_frames];
+        interleave(&refs, num_frames, &mut fast);
+        interleave_scalar(&refs, num_frames, &mut scalar);
+        let fast_bits: Vec<u32> = fast.iter().map(|s| s.to_bits()).collect();
+        let scalar_bits: Vec<u32> = scalar.iter().map(|s| s.to_bits()).collect();
+        assert_eq!(fast_bits, scalar_bits, "{} channels", num_channels);
+
+        let mut out_fast = vec![vec![0f32; num_frames]; num_channels];
+        let mut out_scalar = vec![vec![0f32; num_frames]; num_channels];
+        {
+            let mut dst: Vec<&mut [f32]> = out_fast.iter_mut().map(|v| v.as_mut_slice()).collect();
+            deinterleave(&fast, &mut dst, num_frames);
+        }
+        {
+            let mut dst: Vec<&mut [f32]> =
+                out_scalar.iter_mut().map(|v| v.as_mut_slice()).collect();
+            deinterleave_scalar(&scalar, &mut dst, num_frames);
+        }
+        for ch in 0..num_channels {
+            let a: Vec<u32> = out_fast[ch].iter().map(|s| s.to_bits()).collect();
+            let b: Vec<u32> = out_scalar[ch].iter().map(|s| s.to_bits()).collect();
+            let orig: Vec<u32> = planar[ch].iter().map(|s| s.to_bits()).collect();
+            assert_eq!(a, b, "{} channels, channel {}", num_channels, ch);
+            assert_eq!(
+                a, orig,
+                "round trip, {} channels, channel {}",
+                num_channels, ch
+            );
+        }
+    }
+}
+
+#[test]
+fn interleave_i16_stereo_matches_scalar() {
+    let l: Vec<i16> = (0..64).map(|i| i * 3).collect();
+    let r: Vec<i16> = (0..64).map(|i| -i * 5).collect();
+    let mut fast = vec![0i16; 128];
+    let mut scalar = vec![0i16; 128];
+    interleave(&[&l[..], &r[..]], 64, &mut fast);
+    interleave_scalar(&[&l[..], &r[..]], 64, &mut scalar);
+    assert_eq!(fast, scalar);
+}
+
+/// Rough throughput comparison; run with
+/// `cargo test -p resonance-cxx --release --test interleave -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn bench_interleave_vs_scalar() {
+    let num_frames = 4096;
+    let iterations = 2000;
+    for num_channels in [2usize, 6] {
+        let planar = planar_f32(num_channels, num_frames);
+        let refs: Vec<&[f32]> = planar.iter().map(|v| v.as_slice()).collect();
+        let mut out = vec![0f32; num_channels * num_frames];
+
+        let t = Instant::now();
+        for _ in 0..iterations {
+            interleave_scalar(&refs, num_frames, &mut out);
+            std::hint::black_box(&mut out);
+        }
+        let scalar = t.elapsed();
+
+        let t = Instant::now();
+        for _ in 0..iterations {
+            interleave(&refs, num_frames, &mut out);
+            std::hint::black_box(&mut out);
+        }
+        let fast = t.elapsed();
+
+        println!(
+            "{} ch x {} frames x {}: scalar {:?}, fast {:?}",
+            num_channels, num_frames, iterations, scalar, fast
+        );
+    }
+}
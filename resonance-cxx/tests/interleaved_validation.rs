@@ -0,0 +1,21 @@
+use resonance_cxx::{Api, ResonanceError};
+
+#[test]
+fn short_interleaved_buffer_is_rejected_before_ffi() {
+    let mut api = Api::new(2, 64, 48000).expect("failed to create Api");
+    let src = api.create_stereo_source(2);
+
+    let short = vec![0.1f32; 64]; // needs 2 * 64
+    assert_eq!(
+        api.try_set_interleaved_buffer_f32(src, &short, 2, 64),
+        Err(ResonanceError::BufferLengthMismatch {
+            expected: 128,
+            actual: 64
+        })
+    );
+    assert!(!api.set_interleaved_buffer_f32(src, &short, 2, 64));
+    assert!(!api.set_interleaved_buffer_i16(src, &[0i16; 130], 2, 64));
+
+    assert!(api.set_interleaved_buffer_f32(src, &[0.1f32; 128], 2, 64));
+    assert_eq!(api.try_set_interleaved_buffer_i16(src, &[0i16; 128], 2, 64), Ok(()));
+}
@@ -57,3 +57,40 @@ fn large_buffers_set_f32() {
     println!("set_planar_buffer_f32 returned = {}", ok);
     assert!(ok, "large buffer set failed");
 }
+
+#[test]
+fn try_set_planar_reports_empty_and_mismatched_channels() {
+    use resonance_cxx::ResonanceError;
+
+    let mut api = Api::new(2, 64, 48000).expect("failed to create Api");
+    let src = api.create_stereo_source(2);
+
+    let empty_in: Vec<&[f32]> = vec![];
+    assert_eq!(
+        api.try_set_planar_buffer_f32(src, &empty_in[..], 64),
+        Err(ResonanceError::NoChannels)
+    );
+
+    let c0 = [0f32; 64];
+    let c1 = [0f32; 48];
+    assert_eq!(
+        api.try_set_planar_buffer_f32(src, &[&c0[..], &c1[..]], 64),
+        Err(ResonanceError::FrameCountMismatch {
+            channel: 1,
+            expected: 64,
+            actual: 48
+        })
+    );
+    // num_frames disagreeing with every channel is caught on the first one.
+    let i0 = [0i16; 64];
+    assert_eq!(
+        api.try_set_planar_buffer_i16(src, &[&i0[..], &i0[..]], 32),
+        Err(ResonanceError::FrameCountMismatch {
+            channel: 0,
+            expected: 32,
+            actual: 64
+        })
+    );
+
+    assert_eq!(api.try_set_planar_buffer_f32(src, &[&c0[..], &c0[..]], 64), Ok(()));
+}
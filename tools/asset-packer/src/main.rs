@@ -85,7 +85,34 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // - assets/sfx -> convert eligible files to .sfx and pack as Sfx
     // - assets/audio -> pack raw files as Music
     // Output: assets/dest/out.pkg
-    if args.len() == 2 && args[1] == "--pack-assets" {
+    if args.len() >= 2 && args[1] == "--pack-assets" {
+        // optional: --quality fast|balanced|high (resampler quality for sfx conversion)
+        //           --target-rate <hz>|keep (keep = leave sfx at their source rate)
+        //           --strict (fail on NaN/inf decoder output instead of scrubbing)
+        let mut options = asset_utils::ConvertOptions::default();
+        let mut i = 2;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--quality" => {
+                    i += 1;
+                    options.quality = args.get(i).map(String::as_str).unwrap_or("").parse()?;
+                }
+                "--target-rate" => {
+                    i += 1;
+                    options.target_rate = match args.get(i).map(String::as_str) {
+                        Some("keep") => None,
+                        Some(hz) => Some(hz.parse()?),
+                        None => return Err("--target-rate needs a value".into()),
+                    };
+                }
+                "--strict" => options.strict = true,
+                other => {
+                    eprintln!("unknown option: {}", other);
+                    std::process::exit(1);
+                }
+            }
+            i += 1;
+        }
         let repo_root = Path::new(".");
         let sfx_dir = repo_root.join("assets/sfx");
         let audio_dir = repo_root.join("assets/audio");
@@ -125,10 +152,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     if let Some(ext) = p.extension().and_then(|s| s.to_str()).map(|s| s.to_lowercase()) {
                         match ext.as_str() {
                             "wav" | "ogg" | "opus" => {
-                                let bytes = convert_to_sfx_bytes(&p)?;
+                                let bytes = convert_to_sfx_bytes(&p, &options)?;
+                                let (sr, ch) = asset_utils::sfx_bytes_format(&bytes).unwrap_or_default();
                                 let name = p.with_extension("sfx");
                                 let name = name.strip_prefix(repo_root).unwrap_or(&name).to_string_lossy().into_owned();
-                                entries.push((name, bytes, AssetType::Sfx, 48000u32, 2u16));
+                                entries.push((name, bytes, AssetType::Sfx, sr, ch));
                             }
                             "sfx" => {
                                 let data = fs::read(&p)?;
@@ -0,0 +1,95 @@
+//! Planar <-> interleaved copies used around the rubato resampler.
+//!
+//! Same helpers as `resonance_cxx::interleave`, kept here because the tools
+//! must not link the native Resonance library. The nested per-frame/per-channel
+//! loop is the reference implementation (`*_scalar`); the public entry points
+//! special-case mono and stereo and fall back to channel-strided copies for
+//! wider layouts. All paths produce bit-identical output.
+
+/// Interleave `channels` (each at least `num_frames` long) into `out`, which
+/// must hold at least `channels.len() * num_frames` samples.
+pub fn interleave<T: Copy>(channels: &[&[T]], num_frames: usize, out: &mut [T]) {
+    let num_channels = channels.len();
+    let out = &mut out[..num_channels * num_frames];
+    match channels {
+        [] => {}
+        [mono] => out.copy_from_slice(&mono[..num_frames]),
+        [left, right] => {
+            for ((frame, &l), &r) in out
+                .chunks_exact_mut(2)
+                .zip(&left[..num_frames])
+                .zip(&right[..num_frames])
+            {
+                frame[0] = l;
+                frame[1] = r;
+            }
+        }
+        _ => {
+            for (ch, src) in channels.iter().enumerate() {
+                for (dst, &s) in out[ch..]
+                    .iter_mut()
+                    .step_by(num_channels)
+                    .zip(&src[..num_frames])
+                {
+                    *dst = s;
+                }
+            }
+        }
+    }
+}
+
+/// Split `interleaved` into `channels` (each at least `num_frames` long).
+pub fn deinterleave<T: Copy>(interleaved: &[T], channels: &mut [&mut [T]], num_frames: usize) {
+    let num_channels = channels.len();
+    let interleaved = &interleaved[..num_channels * num_frames];
+    match channels {
+        [] => {}
+        [mono] => mono[..num_frames].copy_from_slice(interleaved),
+        [left, right] => {
+            for ((frame, l), r) in interleaved
+                .chunks_exact(2)
+                .zip(left[..num_frames].iter_mut())
+                .zip(right[..num_frames].iter_mut())
+            {
+                *l = frame[0];
+                *r = frame[1];
+            }
+        }
+        _ => {
+            for (ch, dst) in channels.iter_mut().enumerate() {
+                for (d, &s) in dst[..num_frames]
+                    .iter_mut()
+                    .zip(interleaved[ch..].iter().step_by(num_channels))
+                {
+                    *d = s;
+                }
+            }
+        }
+    }
+}
+
+/// Reference implementation of `interleave`.
+#[allow(clippy::needless_range_loop)]
+pub fn interleave_scalar<T: Copy>(channels: &[&[T]], num_frames: usize, out: &mut [T]) {
+    let num_channels = channels.len();
+    for frame in 0..num_frames {
+        for ch in 0..num_channels {
+            out[frame * num_channels + ch] = channels[ch][frame];
+        }
+    }
+}
+
+/// Reference implementation of `deinterleave`.
+#[allow(clippy::needless_range_loop)]
+pub fn deinterleave_scalar<T: Copy>(
+    interleaved: &[T],
+    channels: &mut [&mut [T]],
+    num_frames: usize,
+) {
+    let num_channels = channels.len();
+    for frame in 0..num_frames {
+        for ch in 0..num_channels {
+            channels[ch][frame] = interleaved[frame * num_channels + ch];
+        }
+    }
+}
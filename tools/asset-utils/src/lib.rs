@@ -1,137 +1,406 @@
+use std::io::Cursor;
 use std::path::Path;
+use std::str::FromStr;
+
+use rubato::{InterpolationParameters, InterpolationType, WindowFunction};
+use thiserror::Error;
+
+pub mod interleave;
+mod stream;
+
+pub use stream::convert_to_sfx_writer;
 
 const TARGET_SAMPLE_RATE: u32 = 48000;
 
-pub fn convert_to_sfx_bytes(path: &Path) -> anyhow::Result<Vec<u8>> {
-    use symphonia::core::codecs::DecoderOptions;
-    use symphonia::core::formats::FormatOptions;
-    use symphonia::core::io::MediaSourceStream;
-    use symphonia::core::meta::MetadataOptions;
-    use symphonia::default::get_probe;
-
-    let file = std::fs::File::open(path)?;
-    let mss = MediaSourceStream::new(Box::new(file), Default::default());
-    let probed = get_probe().format(
-        &Default::default(),
-        mss,
-        &FormatOptions::default(),
-        &MetadataOptions::default(),
-    )?;
-    let mut format = probed.format;
-    let track = format
-        .default_track()
-        .ok_or_else(|| anyhow::anyhow!("no default track"))?;
-    let sr = track
-        .codec_params
-        .sample_rate
-        .ok_or_else(|| anyhow::anyhow!("sample rate unknown"))?;
-    let channels = track.codec_params.channels.map(|c| c.count()).unwrap_or(2) as usize;
-
-    let mut decoder =
-        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
-
-    let mut samples: Vec<f32> = Vec::new();
-
-    loop {
-        let packet = match format.next_packet() {
-            Ok(p) => p,
-            Err(symphonia::core::errors::Error::ResetRequired) => break,
-            Err(symphonia::core::errors::Error::IoError(_)) => break,
-            Err(_) => break,
-        };
-        match decoder.decode(&packet) {
-            Ok(audio_buf) => {
-                // Convert decoded audio to interleaved f32 using SampleBuffer
-                use symphonia::core::audio::SampleBuffer;
-                let spec = *audio_buf.spec();
-                let mut sample_buf = SampleBuffer::<f32>::new(audio_buf.capacity() as u64, spec);
-                sample_buf.copy_interleaved_ref(audio_buf);
-                let s = sample_buf.samples();
-                samples.extend_from_slice(s);
-            }
-            Err(symphonia::core::errors::Error::DecodeError(_)) => continue,
-            Err(_) => break,
+/// `SFX2` header flag: a `[start, end)` loop section follows the base header.
+const SFX2_FLAG_LOOP: u8 = 0x01;
+
+/// Largest up/down conversion factor `resample_interleaved` will accept.
+/// Anything beyond this is almost certainly a bad header rather than a real asset.
+pub const MAX_RESAMPLE_RATIO: f64 = 32.0;
+
+/// Default peak level used when `ConvertOptions::normalize` is enabled.
+pub const DEFAULT_NORMALIZE_PEAK_DBFS: f32 = -1.0;
+
+/// Minimum number of frames the sinc resampler can meaningfully process.
+const MIN_RESAMPLE_FRAMES: usize = 2;
+
+#[derive(Error, Debug)]
+pub enum ConvertError {
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+    #[error("unsupported resample ratio {from_rate} Hz -> {to_rate} Hz")]
+    UnsupportedRatio { from_rate: u32, to_rate: u32 },
+    #[error("failed to create resampler: {0}")]
+    ResamplerCreation(String),
+    #[error("resample failed: {0}")]
+    Resample(String),
+    #[error("decoded audio contains {count} non-finite sample(s)")]
+    NonFiniteSamples { count: usize },
+    #[error("unsupported channel conversion {from} -> {to}")]
+    UnsupportedChannelLayout { from: u16, to: u16 },
+}
+
+/// Speed/quality trade-off for the sinc resampler used during conversion.
+///
+/// `High` is what shipping builds should use; `Fast` is meant for quick
+/// iteration where packing time matters more than the last bit of fidelity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResampleQuality {
+    Fast,
+    Balanced,
+    #[default]
+    High,
+}
+
+impl ResampleQuality {
+    fn interpolation_parameters(self) -> InterpolationParameters {
+        match self {
+            ResampleQuality::Fast => InterpolationParameters {
+                sinc_len: 32,
+                f_cutoff: 0.9,
+                interpolation: InterpolationType::Linear,
+                oversampling_factor: 8,
+                window: WindowFunction::Hann2,
+            },
+            ResampleQuality::Balanced => InterpolationParameters {
+                sinc_len: 128,
+                f_cutoff: 0.925,
+                interpolation: InterpolationType::Linear,
+                oversampling_factor: 64,
+                window: WindowFunction::Blackman2,
+            },
+            ResampleQuality::High => InterpolationParameters {
+                sinc_len: 256,
+                f_cutoff: 0.95,
+                interpolation: InterpolationType::Cubic,
+                oversampling_factor: 32,
+                window: WindowFunction::BlackmanHarris2,
+            },
         }
     }
+}
 
-    let out_samples = if sr != TARGET_SAMPLE_RATE {
-        resample_interleaved(&samples, sr, TARGET_SAMPLE_RATE, channels)
-    } else {
-        samples
-    };
+impl FromStr for ResampleQuality {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "fast" => Ok(ResampleQuality::Fast),
+            "balanced" => Ok(ResampleQuality::Balanced),
+            "high" => Ok(ResampleQuality::High),
+            other => Err(format!(
+                "unknown resample quality '{}' (expected fast, balanced or high)",
+                other
+            )),
+        }
+    }
+}
+
+/// Sample encoding written to the SFX1 header's format byte.
+///
+/// `I16` halves the size of assets that don't need float precision; loaders
+/// scale it back to f32 by `1 / i16::MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SfxFormat {
+    /// Interleaved little-endian f32 (format byte 0).
+    #[default]
+    F32,
+    /// Interleaved little-endian i16, clamped to [-1, 1] (format byte 1).
+    I16,
+}
+
+impl SfxFormat {
+    fn header_byte(self) -> u8 {
+        match self {
+            SfxFormat::F32 => 0,
+            SfxFormat::I16 => 1,
+        }
+    }
+}
+
+/// Settings for `convert_to_sfx_bytes` and `convert_to_sfx_writer`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConvertOptions {
+    pub quality: ResampleQuality,
+    /// Output sample rate. `None` keeps the source rate and records it in the
+    /// SFX header, leaving any device-rate conversion to load time.
+    pub target_rate: Option<u32>,
+    /// Fail with `ConvertError::NonFiniteSamples` instead of scrubbing NaN/inf
+    /// decoder output to silence.
+    pub strict: bool,
+    /// Sample encoding of the output.
+    pub format: SfxFormat,
+    /// Loop region `[start, end)` in source frames. When set, the output is
+    /// written as `SFX2` with the region rescaled to the output rate;
+    /// otherwise a plain `SFX1` blob is produced so older loaders keep working.
+    pub loop_points: Option<(u64, u64)>,
+    /// Scale the decoded audio (before resampling) so its peak sits at
+    /// `normalize_peak_dbfs`. Silent input is left untouched.
+    pub normalize: bool,
+    pub normalize_peak_dbfs: f32,
+    /// Remix to this many channels after decoding; see `remix_channels` for
+    /// the supported conversions. `None` keeps the source layout.
+    pub target_channels: Option<u16>,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            quality: ResampleQuality::default(),
+            target_rate: Some(TARGET_SAMPLE_RATE),
+            strict: false,
+            format: SfxFormat::default(),
+            loop_points: None,
+            normalize: false,
+            normalize_peak_dbfs: DEFAULT_NORMALIZE_PEAK_DBFS,
+            target_channels: None,
+        }
+    }
+}
+
+/// `convert_to_sfx_bytes` with default options and the given sample format.
+pub fn convert_to_sfx_bytes_fmt(path: &Path, format: SfxFormat) -> anyhow::Result<Vec<u8>> {
+    convert_to_sfx_bytes(
+        path,
+        &ConvertOptions {
+            format,
+            ..ConvertOptions::default()
+        },
+    )
+}
+
+/// Decode `path` and return the complete SFX blob.
+///
+/// Thin wrapper over `convert_to_sfx_writer` writing into memory, so both
+/// entry points produce identical bytes.
+pub fn convert_to_sfx_bytes(path: &Path, options: &ConvertOptions) -> anyhow::Result<Vec<u8>> {
+    let mut out = Cursor::new(Vec::new());
+    convert_to_sfx_writer(path, options, &mut out)?;
+    Ok(out.into_inner())
+}
+
+/// Replace NaN/inf samples with 0.0 in place, returning how many were found.
+pub fn scrub_non_finite(samples: &mut [f32]) -> usize {
+    let mut count = 0;
+    for s in samples.iter_mut() {
+        if !s.is_finite() {
+            *s = 0.0;
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Convert interleaved `samples` from `from` to `to` channels.
+///
+/// Supported: any layout to mono (average of all channels), mono to stereo
+/// (duplication), and 5.1 (L R C LFE Ls Rs) to stereo using the ITU-R BS.775
+/// matrix (centre and surrounds at -3 dB, LFE dropped), scaled down so a
+/// full-scale input can't clip. Anything else is
+/// `ConvertError::UnsupportedChannelLayout`.
+pub fn remix_channels(samples: &[f32], from: u16, to: u16) -> Result<Vec<f32>, ConvertError> {
+    let mut out = Vec::new();
+    remix_into(samples, from, to, &mut out)?;
+    Ok(out)
+}
 
-    let bytes = write_sfx_bytes(&out_samples, TARGET_SAMPLE_RATE, channels as u16)?;
-    Ok(bytes)
+/// `remix_channels` into a caller-owned buffer, which is cleared first so
+/// streaming conversion can reuse one allocation across blocks.
+fn remix_into(samples: &[f32], from: u16, to: u16, out: &mut Vec<f32>) -> Result<(), ConvertError> {
+    if from == 0 || to == 0 {
+        return Err(ConvertError::UnsupportedChannelLayout { from, to });
+    }
+    let from_n = from as usize;
+    if !samples.len().is_multiple_of(from_n) {
+        return Err(ConvertError::InvalidInput(format!(
+            "{} samples is not a whole number of {}-channel frames",
+            samples.len(),
+            from
+        )));
+    }
+    out.clear();
+    let frames = samples.chunks_exact(from_n);
+    match (from, to) {
+        _ if from == to => out.extend_from_slice(samples),
+        (_, 1) => out.extend(frames.map(|f| f.iter().sum::<f32>() / from as f32)),
+        (1, 2) => out.extend(samples.iter().flat_map(|&s| [s, s])),
+        (6, 2) => {
+            const MINUS_3DB: f32 = std::f32::consts::FRAC_1_SQRT_2;
+            let norm = 1.0 / (1.0 + 2.0 * MINUS_3DB);
+            out.extend(frames.flat_map(|f| {
+                let (l, r, c, ls, rs) = (f[0], f[1], f[2], f[4], f[5]);
+                [
+                    (l + MINUS_3DB * (c + ls)) * norm,
+                    (r + MINUS_3DB * (c + rs)) * norm,
+                ]
+            }))
+        }
+        _ => return Err(ConvertError::UnsupportedChannelLayout { from, to }),
+    }
+    Ok(())
+}
+
+/// Scale `samples` in place so the absolute peak equals `target_dbfs`,
+/// returning the linear gain applied. All-silent input returns 1.0 unchanged.
+pub fn normalize_peak(samples: &mut [f32], target_dbfs: f32) -> f32 {
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    let gain = normalize_gain(peak, target_dbfs);
+    if gain != 1.0 {
+        for s in samples.iter_mut() {
+            *s *= gain;
+        }
+    }
+    gain
+}
+
+/// Linear gain that brings an absolute `peak` to `target_dbfs`; 1.0 for
+/// silent or non-finite peaks.
+fn normalize_gain(peak: f32, target_dbfs: f32) -> f32 {
+    if peak <= f32::EPSILON || !peak.is_finite() {
+        return 1.0;
+    }
+    10f32.powf(target_dbfs / 20.0) / peak
 }
 
-fn resample_interleaved(
+/// Resample interleaved `samples` from `from_rate` to `to_rate`.
+///
+/// Returns an error instead of panicking for degenerate input (zero channels,
+/// ragged sample counts, fewer than two frames) and for ratios outside
+/// `1/MAX_RESAMPLE_RATIO..=MAX_RESAMPLE_RATIO`.
+pub fn resample_interleaved(
     samples: &[f32],
     from_rate: u32,
     to_rate: u32,
     channels: usize,
-) -> Vec<f32> {
+    quality: ResampleQuality,
+) -> Result<Vec<f32>, ConvertError> {
+    if channels == 0 {
+        return Err(ConvertError::InvalidInput("channel count is zero".into()));
+    }
+    if !samples.len().is_multiple_of(channels) {
+        return Err(ConvertError::InvalidInput(format!(
+            "{} samples is not a whole number of {}-channel frames",
+            samples.len(),
+            channels
+        )));
+    }
     if from_rate == to_rate || samples.is_empty() {
-        return samples.to_vec();
+        return Ok(samples.to_vec());
     }
-    use rubato::{
-        InterpolationParameters, InterpolationType, Resampler, SincFixedIn, WindowFunction,
-    };
-
+    let ratio = check_resample_ratio(from_rate, to_rate)?;
     let frames = samples.len() / channels;
-    let ratio = to_rate as f64 / from_rate as f64;
-    let params = InterpolationParameters {
-        sinc_len: 256,
-        f_cutoff: 0.95,
-        interpolation: InterpolationType::Cubic,
-        oversampling_factor: 32,
-        window: WindowFunction::BlackmanHarris2,
-    };
-    let chunk_size = frames.max(1024);
+    if frames < MIN_RESAMPLE_FRAMES {
+        return Err(ConvertError::InvalidInput(format!(
+            "cannot resample {} frame(s); need at least {}",
+            frames, MIN_RESAMPLE_FRAMES
+        )));
+    }
 
-    let mut planar: Vec<Vec<f32>> = vec![Vec::with_capacity(frames); channels];
-    for f in 0..frames {
-        for ch in 0..channels {
-            planar[ch].push(samples[f * channels + ch]);
-        }
+    use rubato::{Resampler, SincFixedIn};
+
+    let params = quality.interpolation_parameters();
+    let sinc_len = params.sinc_len;
+    // The whole clip is processed as one chunk. Pad with a sinc length of
+    // silence so the filter tail is flushed out, then trim to the exact
+    // output length below.
+    let chunk_size = frames + sinc_len;
+
+    let mut planar: Vec<Vec<f32>> = vec![vec![0.0; chunk_size]; channels];
+    {
+        let mut dst: Vec<&mut [f32]> = planar.iter_mut().map(|v| v.as_mut_slice()).collect();
+        interleave::deinterleave(samples, &mut dst, frames);
     }
 
-    let cutoff_scale: f64 = 0.95;
-    // SincFixedIn in this rubato version expects the f_cutoff parameter first,
-    // then the max_resample_ratio_relative. Also ensure the max ratio is >= 1.0.
-    let max_ratio = if ratio < 1.0 { 1.0 } else { ratio };
-    // rubato's SincFixedIn in this version expects chunk_size before channels.
-    let mut resampler = SincFixedIn::<f32>::new(cutoff_scale, max_ratio, params, chunk_size, channels)
-        .expect("failed to create resampler");
+    let mut resampler = SincFixedIn::<f32>::new(ratio, 1.0, params, chunk_size, channels)
+        .map_err(|e| ConvertError::ResamplerCreation(e.to_string()))?;
     let input_refs: Vec<&[f32]> = planar.iter().map(|v| v.as_slice()).collect();
     let outputs = resampler
         .process(&input_refs, None)
-        .expect("resample failed");
+        .map_err(|e| ConvertError::Resample(e.to_string()))?;
 
     if outputs.is_empty() {
-        return Vec::new();
+        return Ok(Vec::new());
     }
-    let out_frames = outputs[0].len();
+    let out_frames = ((frames as f64 * ratio).round() as usize).min(outputs[0].len());
     let mut out = vec![0.0f32; out_frames * channels];
-    for f in 0..out_frames {
-        for ch in 0..channels {
-            out[f * channels + ch] = outputs[ch][f];
-        }
+    let output_refs: Vec<&[f32]> = outputs.iter().map(|v| v.as_slice()).collect();
+    interleave::interleave(&output_refs, out_frames, &mut out);
+    Ok(out)
+}
+
+/// `to_rate / from_rate`, or `UnsupportedRatio` if either rate is zero or the
+/// factor exceeds `MAX_RESAMPLE_RATIO`.
+fn check_resample_ratio(from_rate: u32, to_rate: u32) -> Result<f64, ConvertError> {
+    if from_rate == 0 || to_rate == 0 {
+        return Err(ConvertError::UnsupportedRatio { from_rate, to_rate });
+    }
+    let ratio = to_rate as f64 / from_rate as f64;
+    if !(1.0 / MAX_RESAMPLE_RATIO..=MAX_RESAMPLE_RATIO).contains(&ratio) {
+        return Err(ConvertError::UnsupportedRatio { from_rate, to_rate });
     }
-    out
+    Ok(ratio)
+}
+
+/// Read `(sample_rate, channels)` back out of an `SFX1`/`SFX2` header produced
+/// by `convert_to_sfx_bytes`.
+pub fn sfx_bytes_format(bytes: &[u8]) -> Option<(u32, u16)> {
+    if bytes.len() < 20 || !(&bytes[0..4] == b"SFX1" || &bytes[0..4] == b"SFX2") {
+        return None;
+    }
+    let channels = bytes[5] as u16;
+    let sample_rate = u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+    Some((sample_rate, channels))
+}
+
+/// Frame count recorded in an `SFX1`/`SFX2` header. Independent of the sample
+/// format and of any `SFX2` loop section, unlike the byte length.
+pub fn sfx_bytes_frames(bytes: &[u8]) -> Option<u64> {
+    sfx_bytes_format(bytes)?;
+    Some(u64::from_le_bytes(bytes[12..20].try_into().ok()?))
 }
 
-fn write_sfx_bytes(samples: &[f32], sample_rate: u32, channels: u16) -> anyhow::Result<Vec<u8>> {
-    let mut v: Vec<u8> = Vec::with_capacity(20 + samples.len() * 4);
-    v.extend_from_slice(b"SFX1");
-    v.push(0u8); // F32
+/// `SFX1` header, or `SFX2` with its loop section when `loop_points` is set.
+fn sfx_header(
+    format: SfxFormat,
+    channels: u16,
+    sample_rate: u32,
+    frames: u64,
+    loop_points: Option<(u64, u64)>,
+) -> Vec<u8> {
+    let mut v: Vec<u8> = Vec::with_capacity(36);
+    // SFX2 flags (bit 0: loop section present); reserved in SFX1.
+    let (magic, flags) = match loop_points {
+        Some(_) => (b"SFX2", SFX2_FLAG_LOOP),
+        None => (b"SFX1", 0u8),
+    };
+    v.extend_from_slice(magic);
+    v.push(format.header_byte());
     v.push(channels as u8);
-    v.push(0u8);
+    v.push(flags);
     v.push(0u8);
     v.extend_from_slice(&sample_rate.to_le_bytes());
-    let frames: u64 = (samples.len() / channels as usize) as u64;
     v.extend_from_slice(&frames.to_le_bytes());
-    for s in samples {
-        v.extend_from_slice(&s.to_le_bytes());
+    if let Some((start, end)) = loop_points {
+        v.extend_from_slice(&start.to_le_bytes());
+        v.extend_from_slice(&end.to_le_bytes());
+    }
+    v
+}
+
+/// Append `samples` to `out` in the given encoding.
+fn encode_samples(samples: &[f32], format: SfxFormat, out: &mut Vec<u8>) {
+    match format {
+        SfxFormat::F32 => {
+            for s in samples {
+                out.extend_from_slice(&s.to_le_bytes());
+            }
+        }
+        SfxFormat::I16 => {
+            for s in samples {
+                let q = (s.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+                out.extend_from_slice(&q.to_le_bytes());
+            }
+        }
     }
-    Ok(v)
 }
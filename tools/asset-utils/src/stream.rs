@@ -0,0 +1,396 @@
+//! Chunked decode -> remix -> resample -> encode pipeline behind
+//! `convert_to_sfx_writer`.
+//!
+//! Memory stays bounded by the decoder's packet size and the resampler chunk
+//! rather than the asset length, so long music tracks convert without holding
+//! the whole decoded clip. The SFX header is written up front with zeroed
+//! sizes and patched once the stream ends.
+
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+use rubato::{Resampler, SincFixedIn};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{Decoder, DecoderOptions};
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+
+use crate::interleave::{deinterleave, interleave};
+use crate::{
+    check_resample_ratio, encode_samples, normalize_gain, remix_into, sfx_header, ConvertError,
+    ConvertOptions, ResampleQuality, MIN_RESAMPLE_FRAMES,
+};
+
+/// Input frames handed to the resampler per call.
+const RESAMPLE_CHUNK_FRAMES: usize = 4096;
+
+// Offsets of the fields patched after streaming.
+const FRAMES_OFFSET: u64 = 12;
+const LOOP_OFFSET: u64 = 20;
+
+/// Decode `path` and write the SFX blob to `out` in bounded-size chunks.
+///
+/// Produces exactly the bytes `convert_to_sfx_bytes` returns for the same
+/// options. `out` must be seekable because the frame count (and any loop
+/// region) is only known at the end. With `normalize` set the file is decoded
+/// twice: once to find the peak, once to write. On error the contents written
+/// to `out` so far are unspecified.
+pub fn convert_to_sfx_writer<W: Write + Seek>(
+    path: &Path,
+    options: &ConvertOptions,
+    out: &mut W,
+) -> anyhow::Result<()> {
+    let gain = if options.normalize {
+        let mut peak = 0.0f32;
+        let mut source = DecodedSource::open(path)?;
+        let mut shaper = BlockShaper::new(source.channels, options.target_channels);
+        source.for_each_block(|block| {
+            let shaped = shaper.shape(block)?;
+            peak = shaped.iter().fold(peak, |m, s| m.max(s.abs()));
+            Ok(())
+        })?;
+        normalize_gain(peak, options.normalize_peak_dbfs)
+    } else {
+        1.0
+    };
+
+    let mut source = DecodedSource::open(path)?;
+    let sr = source.sample_rate;
+    let out_rate = options.target_rate.unwrap_or(sr);
+    let mut shaper = BlockShaper::new(source.channels, options.target_channels);
+    let channels = shaper.out_channels;
+
+    let mut resampler = if sr != out_rate {
+        Some(ChunkedResampler::new(
+            sr,
+            out_rate,
+            channels,
+            options.quality,
+        )?)
+    } else {
+        None
+    };
+
+    let base = out.stream_position()?;
+    out.write_all(&sfx_header(
+        options.format,
+        channels as u16,
+        out_rate,
+        0,
+        options.loop_points.map(|_| (0, 0)),
+    ))?;
+
+    let mut src_frames = 0u64;
+    let mut out_frames = 0u64;
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut emit = |samples: &[f32], out: &mut W| -> std::io::Result<()> {
+        bytes.clear();
+        encode_samples(samples, options.format, &mut bytes);
+        out_frames += (samples.len() / channels) as u64;
+        out.write_all(&bytes)
+    };
+
+    source.for_each_block(|block| {
+        let shaped = shaper.shape(block)?;
+        if gain != 1.0 {
+            shaped.iter_mut().for_each(|s| *s *= gain);
+        }
+        src_frames += (shaped.len() / channels) as u64;
+        match resampler.as_mut() {
+            Some(r) => r.push(shaped, |s| emit(s, out))?,
+            None => emit(shaped, out)?,
+        }
+        Ok(())
+    })?;
+
+    if shaper.non_finite > 0 {
+        if options.strict {
+            return Err(ConvertError::NonFiniteSamples {
+                count: shaper.non_finite,
+            }
+            .into());
+        }
+        eprintln!(
+            "warning: {}: replaced {} non-finite sample(s) with silence",
+            path.display(),
+            shaper.non_finite
+        );
+    }
+
+    if let Some((start, end)) = options.loop_points {
+        if start >= end || end > src_frames {
+            return Err(ConvertError::InvalidInput(format!(
+                "loop region {}..{} is outside the {} source frames",
+                start, end, src_frames
+            ))
+            .into());
+        }
+    }
+
+    if let Some(r) = resampler.take() {
+        r.finish(|s| emit(s, out))?;
+    }
+
+    let loop_points = options.loop_points.map(|(start, end)| {
+        let ratio = out_rate as f64 / sr as f64;
+        let end = ((end as f64 * ratio).round() as u64).min(out_frames);
+        let start = ((start as f64 * ratio).round() as u64).min(end.saturating_sub(1));
+        (start, end)
+    });
+
+    let end = out.stream_position()?;
+    out.seek(SeekFrom::Start(base + FRAMES_OFFSET))?;
+    out.write_all(&out_frames.to_le_bytes())?;
+    if let Some((start, end)) = loop_points {
+        out.seek(SeekFrom::Start(base + LOOP_OFFSET))?;
+        out.write_all(&start.to_le_bytes())?;
+        out.write_all(&end.to_le_bytes())?;
+    }
+    out.seek(SeekFrom::Start(end))?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Symphonia reader/decoder pair for the default track of a file.
+struct DecodedSource {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    sample_rate: u32,
+    channels: usize,
+}
+
+impl DecodedSource {
+    fn open(path: &Path) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let mss = MediaSourceStream::new(Box::new(file), Default::default());
+        let probed = symphonia::default::get_probe().format(
+            &Default::default(),
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )?;
+        let format = probed.format;
+        let track = format
+            .default_track()
+            .ok_or_else(|| anyhow::anyhow!("no default track"))?;
+        let sample_rate = track
+            .codec_params
+            .sample_rate
+            .ok_or_else(|| anyhow::anyhow!("sample rate unknown"))?;
+        let channels = track.codec_params.channels.map(|c| c.count()).unwrap_or(2);
+        let decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())?;
+        Ok(Self {
+            format,
+            decoder,
+            sample_rate,
+            channels,
+        })
+    }
+
+    /// Call `f` with each decoded packet as interleaved f32.
+    fn for_each_block(
+        &mut self,
+        mut f: impl FnMut(&[f32]) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let mut sample_buf: Option<SampleBuffer<f32>> = None;
+        // Any reader error, end of stream included, stops decoding.
+        while let Ok(packet) = self.format.next_packet() {
+            match self.decoder.decode(&packet) {
+                Ok(audio_buf) => {
+                    let spec = *audio_buf.spec();
+                    let capacity = audio_buf.capacity() as u64;
+                    if sample_buf
+                        .as_ref()
+                        .is_none_or(|b| b.capacity() < capacity as usize * spec.channels.count())
+                    {
+                        sample_buf = Some(SampleBuffer::new(capacity, spec));
+                    }
+                    let buf = sample_buf.as_mut().unwrap();
+                    buf.copy_interleaved_ref(audio_buf);
+                    f(buf.samples())?;
+                }
+                Err(symphonia::core::errors::Error::DecodeError(_)) => continue,
+                Err(_) => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Per-block scrub + remix, reusing its buffers between blocks.
+struct BlockShaper {
+    in_channels: usize,
+    out_channels: usize,
+    non_finite: usize,
+    scratch: Vec<f32>,
+    remixed: Vec<f32>,
+}
+
+impl BlockShaper {
+    fn new(in_channels: usize, target_channels: Option<u16>) -> Self {
+        Self {
+            in_channels,
+            out_channels: target_channels.map_or(in_channels, |c| c as usize),
+            non_finite: 0,
+            scratch: Vec::new(),
+            remixed: Vec::new(),
+        }
+    }
+
+    fn shape(&mut self, block: &[f32]) -> Result<&mut [f32], ConvertError> {
+        self.scratch.clear();
+        self.scratch.extend_from_slice(block);
+        // Corrupt input can decode to NaN/inf; never let that reach the mixer.
+        self.non_finite += crate::scrub_non_finite(&mut self.scratch);
+        if self.out_channels == self.in_channels {
+            return Ok(&mut self.scratch);
+        }
+        remix_into(
+            &self.scratch,
+            self.in_channels as u16,
+            self.out_channels as u16,
+            &mut self.remixed,
+        )?;
+        Ok(&mut self.remixed)
+    }
+}
+
+/// Drives `SincFixedIn` a chunk at a time.
+///
+/// Like `resample_interleaved`, the tail is flushed with silence and the
+/// total output trimmed to `round(frames_in * ratio)`. Until the input ends,
+/// output is held back past `floor(frames_in * ratio)` so nothing beyond the
+/// final length is ever emitted.
+struct ChunkedResampler {
+    resampler: SincFixedIn<f32>,
+    channels: usize,
+    ratio: f64,
+    pending: Vec<Vec<f32>>,
+    held: Vec<f32>,
+    frames_in: u64,
+    frames_out: u64,
+    scratch: Vec<f32>,
+}
+
+impl ChunkedResampler {
+    fn new(
+        from_rate: u32,
+        to_rate: u32,
+        channels: usize,
+        quality: ResampleQuality,
+    ) -> Result<Self, ConvertError> {
+        if channels == 0 {
+            return Err(ConvertError::InvalidInput("channel count is zero".into()));
+        }
+        let ratio = check_resample_ratio(from_rate, to_rate)?;
+        let resampler = SincFixedIn::<f32>::new(
+            ratio,
+            1.0,
+            quality.interpolation_parameters(),
+            RESAMPLE_CHUNK_FRAMES,
+            channels,
+        )
+        .map_err(|e| ConvertError::ResamplerCreation(e.to_string()))?;
+        Ok(Self {
+            resampler,
+            channels,
+            ratio,
+            pending: vec![Vec::with_capacity(RESAMPLE_CHUNK_FRAMES); channels],
+            held: Vec::new(),
+            frames_in: 0,
+            frames_out: 0,
+            scratch: Vec::new(),
+        })
+    }
+
+    fn push(
+        &mut self,
+        interleaved: &[f32],
+        mut emit: impl FnMut(&[f32]) -> std::io::Result<()>,
+    ) -> Result<(), anyhow::Error> {
+        let mut rest = interleaved;
+        while rest.len() >= self.channels {
+            // Top the pending chunk up with as many whole frames as fit.
+            let filled = self.pending[0].len();
+            let n = (RESAMPLE_CHUNK_FRAMES - filled).min(rest.len() / self.channels);
+            let (span, tail) = rest.split_at(n * self.channels);
+            let mut dst: Vec<&mut [f32]> = self
+                .pending
+                .iter_mut()
+                .map(|ch| {
+                    ch.resize(filled + n, 0.0);
+                    &mut ch[filled..]
+                })
+                .collect();
+            deinterleave(span, &mut dst, n);
+            rest = tail;
+            if filled + n == RESAMPLE_CHUNK_FRAMES {
+                self.process_pending()?;
+            }
+        }
+        self.frames_in += (interleaved.len() / self.channels) as u64;
+        let limit = (self.frames_in as f64 * self.ratio).floor() as u64;
+        self.emit_up_to(limit, &mut emit)?;
+        Ok(())
+    }
+
+    fn finish(mut self, mut emit: impl FnMut(&[f32]) -> std::io::Result<()>) -> anyhow::Result<()> {
+        if self.frames_in == 0 {
+            return Ok(());
+        }
+        if (self.frames_in as usize) < MIN_RESAMPLE_FRAMES {
+            return Err(ConvertError::InvalidInput(format!(
+                "cannot resample {} frame(s); need at least {}",
+                self.frames_in, MIN_RESAMPLE_FRAMES
+            ))
+            .into());
+        }
+        let total = (self.frames_in as f64 * self.ratio).round() as u64;
+        // Feed silence until the filter has produced every output frame.
+        while self.frames_out + self.held_frames() < total {
+            for ch in self.pending.iter_mut() {
+                ch.resize(RESAMPLE_CHUNK_FRAMES, 0.0);
+            }
+            self.process_pending()?;
+        }
+        self.emit_up_to(total, &mut emit)?;
+        Ok(())
+    }
+
+    fn process_pending(&mut self) -> Result<(), ConvertError> {
+        let outputs = Resampler::process(&mut self.resampler, &self.pending, None)
+            .map_err(|e| ConvertError::Resample(e.to_string()))?;
+        for ch in self.pending.iter_mut() {
+            ch.clear();
+        }
+        let frames = outputs.first().map_or(0, |o| o.len());
+        let output_refs: Vec<&[f32]> = outputs.iter().map(|v| v.as_slice()).collect();
+        let start = self.held.len();
+        self.held.resize(start + frames * self.channels, 0.0);
+        interleave(&output_refs, frames, &mut self.held[start..]);
+        Ok(())
+    }
+
+    fn held_frames(&self) -> u64 {
+        (self.held.len() / self.channels) as u64
+    }
+
+    fn emit_up_to(
+        &mut self,
+        limit: u64,
+        emit: &mut impl FnMut(&[f32]) -> std::io::Result<()>,
+    ) -> std::io::Result<()> {
+        let n = self
+            .held_frames()
+            .min(limit.saturating_sub(self.frames_out)) as usize;
+        if n == 0 {
+            return Ok(());
+        }
+        self.scratch.clear();
+        self.scratch.extend(self.held.drain(..n * self.channels));
+        self.frames_out += n as u64;
+        emit(&self.scratch)
+    }
+}
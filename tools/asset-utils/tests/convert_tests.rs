@@ -0,0 +1,455 @@
+use std::fs::File;
+use std::io::Write;
+
+use asset_utils::{
+    convert_to_sfx_bytes, convert_to_sfx_bytes_fmt, convert_to_sfx_writer, normalize_peak,
+    remix_channels, resample_interleaved, scrub_non_finite, sfx_bytes_format, sfx_bytes_frames,
+    ConvertError, ConvertOptions, ResampleQuality, SfxFormat,
+};
+use tempfile::tempdir;
+
+/// Minimal 16-bit PCM WAV writer for feeding `convert_to_sfx_bytes`.
+fn wav_bytes(sample_rate: u32, channels: u16, samples: &[i16]) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut v = Vec::new();
+    v.extend_from_slice(b"RIFF");
+    v.extend_from_slice(&(36 + data_len).to_le_bytes());
+    v.extend_from_slice(b"WAVEfmt ");
+    v.extend_from_slice(&16u32.to_le_bytes());
+    v.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    v.extend_from_slice(&channels.to_le_bytes());
+    v.extend_from_slice(&sample_rate.to_le_bytes());
+    v.extend_from_slice(&(sample_rate * channels as u32 * 2).to_le_bytes());
+    v.extend_from_slice(&(channels * 2).to_le_bytes());
+    v.extend_from_slice(&16u16.to_le_bytes());
+    v.extend_from_slice(b"data");
+    v.extend_from_slice(&data_len.to_le_bytes());
+    for s in samples {
+        v.extend_from_slice(&s.to_le_bytes());
+    }
+    v
+}
+
+/// 32-bit float WAV writer, used to smuggle NaN/inf through the decoder.
+fn float_wav_bytes(sample_rate: u32, channels: u16, samples: &[f32]) -> Vec<u8> {
+    let data_len = (samples.len() * 4) as u32;
+    let mut v = Vec::new();
+    v.extend_from_slice(b"RIFF");
+    v.extend_from_slice(&(36 + data_len).to_le_bytes());
+    v.extend_from_slice(b"WAVEfmt ");
+    v.extend_from_slice(&16u32.to_le_bytes());
+    v.extend_from_slice(&3u16.to_le_bytes()); // IEEE float
+    v.extend_from_slice(&channels.to_le_bytes());
+    v.extend_from_slice(&sample_rate.to_le_bytes());
+    v.extend_from_slice(&(sample_rate * channels as u32 * 4).to_le_bytes());
+    v.extend_from_slice(&(channels * 4).to_le_bytes());
+    v.extend_from_slice(&32u16.to_le_bytes());
+    v.extend_from_slice(b"data");
+    v.extend_from_slice(&data_len.to_le_bytes());
+    for s in samples {
+        v.extend_from_slice(&s.to_le_bytes());
+    }
+    v
+}
+
+#[test]
+fn single_frame_input_is_an_error() {
+    let err =
+        resample_interleaved(&[0.5, -0.5], 44100, 48000, 2, ResampleQuality::High).unwrap_err();
+    assert!(matches!(err, ConvertError::InvalidInput(_)));
+}
+
+#[test]
+fn extreme_ratio_is_an_error() {
+    let samples = vec![0.0f32; 1024];
+    let err = resample_interleaved(&samples, 1000, 192000, 1, ResampleQuality::High).unwrap_err();
+    assert!(matches!(
+        err,
+        ConvertError::UnsupportedRatio {
+            from_rate: 1000,
+            to_rate: 192000
+        }
+    ));
+}
+
+#[test]
+fn ragged_interleaved_input_is_an_error() {
+    let err = resample_interleaved(&[0.0; 5], 44100, 48000, 2, ResampleQuality::High).unwrap_err();
+    assert!(matches!(err, ConvertError::InvalidInput(_)));
+}
+
+#[test]
+fn short_input_resamples_to_expected_length() {
+    // Shorter than any internal chunk size; used to panic.
+    let frames = 100;
+    let samples = vec![0.25f32; frames * 2];
+    let out = resample_interleaved(&samples, 24000, 48000, 2, ResampleQuality::High).unwrap();
+    assert_eq!(out.len(), frames * 2 * 2);
+}
+
+#[test]
+fn convert_propagates_resample_error() {
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("one_frame.wav");
+    File::create(&p)
+        .unwrap()
+        .write_all(&wav_bytes(44100, 1, &[1000]))
+        .unwrap();
+    let err = convert_to_sfx_bytes(&p, &ConvertOptions::default()).unwrap_err();
+    assert!(err.downcast_ref::<ConvertError>().is_some());
+}
+
+#[test]
+fn target_rate_none_keeps_source_rate() {
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("tone_44k.wav");
+    let samples: Vec<i16> = (0..441).map(|i| (i * 50) as i16).collect();
+    File::create(&p)
+        .unwrap()
+        .write_all(&wav_bytes(44100, 1, &samples))
+        .unwrap();
+
+    let keep = ConvertOptions {
+        target_rate: None,
+        ..ConvertOptions::default()
+    };
+    let bytes = convert_to_sfx_bytes(&p, &keep).unwrap();
+    assert_eq!(sfx_bytes_format(&bytes), Some((44100, 1)));
+    let frames = u64::from_le_bytes(bytes[12..20].try_into().unwrap());
+    assert_eq!(frames, 441);
+
+    let bytes = convert_to_sfx_bytes(&p, &ConvertOptions::default()).unwrap();
+    assert_eq!(sfx_bytes_format(&bytes), Some((48000, 1)));
+    let frames = u64::from_le_bytes(bytes[12..20].try_into().unwrap());
+    assert_eq!(frames, 480);
+}
+
+#[test]
+fn scrub_non_finite_zeroes_and_counts() {
+    let mut s = [0.5, f32::NAN, f32::INFINITY, -0.5, f32::NEG_INFINITY];
+    assert_eq!(scrub_non_finite(&mut s), 3);
+    assert_eq!(s, [0.5, 0.0, 0.0, -0.5, 0.0]);
+}
+
+#[test]
+fn non_finite_decoder_output_is_scrubbed_or_rejected_in_strict_mode() {
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("corrupt.wav");
+    let mut samples = vec![0.25f32; 64];
+    samples[10] = f32::NAN;
+    samples[20] = f32::INFINITY;
+    File::create(&p)
+        .unwrap()
+        .write_all(&float_wav_bytes(48000, 1, &samples))
+        .unwrap();
+
+    let bytes = convert_to_sfx_bytes(&p, &ConvertOptions::default()).unwrap();
+    let out: Vec<f32> = bytes[20..]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect();
+    assert_eq!(out.len(), 64);
+    assert!(out.iter().all(|s| s.is_finite()));
+    assert_eq!(out[10], 0.0);
+    assert_eq!(out[20], 0.0);
+    assert_eq!(out[11], 0.25);
+
+    let strict = ConvertOptions {
+        strict: true,
+        ..ConvertOptions::default()
+    };
+    let err = convert_to_sfx_bytes(&p, &strict).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ConvertError>(),
+        Some(ConvertError::NonFiniteSamples { count: 2 })
+    ));
+}
+
+#[test]
+fn i16_sfx_round_trips_a_ramp_within_quantization_error() {
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("ramp.wav");
+    // Float source so the i16 step is the only quantization involved.
+    let ramp: Vec<f32> = (0..960).map(|i| i as f32 / 480.0 - 1.0).collect();
+    File::create(&p)
+        .unwrap()
+        .write_all(&float_wav_bytes(48000, 2, &ramp))
+        .unwrap();
+
+    let f32_bytes = convert_to_sfx_bytes_fmt(&p, SfxFormat::F32).unwrap();
+    let i16_bytes = convert_to_sfx_bytes_fmt(&p, SfxFormat::I16).unwrap();
+    assert_eq!(f32_bytes[4], 0);
+    assert_eq!(i16_bytes[4], 1);
+    assert_eq!(i16_bytes.len() - 20, (f32_bytes.len() - 20) / 2);
+    assert_eq!(sfx_bytes_format(&i16_bytes), Some((48000, 2)));
+
+    let (decoded, meta) = asset_manager::sfx_loader::parse_pcm_sfx_data(&i16_bytes).unwrap();
+    assert_eq!((meta.sample_rate, meta.channels), (48000, 2));
+    assert_eq!(decoded.len(), ramp.len());
+    let step = 1.0 / i16::MAX as f32;
+    for (i, (d, r)) in decoded.iter().zip(&ramp).enumerate() {
+        assert!((d - r).abs() <= step, "sample {}: {} vs {}", i, d, r);
+    }
+}
+
+#[test]
+fn loop_points_emit_sfx2_and_rescale_with_the_rate() {
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("amb.wav");
+    let samples: Vec<i16> = (0..441).map(|i| (i * 30) as i16).collect();
+    File::create(&p)
+        .unwrap()
+        .write_all(&wav_bytes(44100, 1, &samples))
+        .unwrap();
+
+    // No loop: unchanged SFX1 output.
+    let plain = convert_to_sfx_bytes(&p, &ConvertOptions::default()).unwrap();
+    assert_eq!(&plain[0..4], b"SFX1");
+
+    let keep = ConvertOptions {
+        target_rate: None,
+        loop_points: Some((100, 400)),
+        ..ConvertOptions::default()
+    };
+    let bytes = convert_to_sfx_bytes(&p, &keep).unwrap();
+    assert_eq!(&bytes[0..4], b"SFX2");
+    assert_eq!(sfx_bytes_format(&bytes), Some((44100, 1)));
+    let (_, meta) = asset_manager::sfx_loader::parse_pcm_sfx_data(&bytes).unwrap();
+    assert_eq!(meta.loop_points, Some((100, 400)));
+
+    // 44.1k -> 48k scales the region with the frames.
+    let resampled = ConvertOptions {
+        loop_points: Some((100, 400)),
+        ..ConvertOptions::default()
+    };
+    let bytes = convert_to_sfx_bytes(&p, &resampled).unwrap();
+    let (samples, meta) = asset_manager::sfx_loader::parse_pcm_sfx_data(&bytes).unwrap();
+    assert_eq!(samples.len(), 480);
+    assert_eq!(meta.loop_points, Some((109, 435)));
+
+    let bad = ConvertOptions {
+        loop_points: Some((400, 500)),
+        ..ConvertOptions::default()
+    };
+    let err = convert_to_sfx_bytes(&p, &bad).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<ConvertError>(),
+        Some(ConvertError::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn normalize_lifts_quiet_ramp_to_target_peak() {
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("quiet.wav");
+    // Peaks at about -30 dBFS.
+    let ramp: Vec<f32> = (0..480).map(|i| (i as f32 / 480.0 - 0.5) * 0.063).collect();
+    File::create(&p)
+        .unwrap()
+        .write_all(&float_wav_bytes(48000, 1, &ramp))
+        .unwrap();
+
+    let opts = ConvertOptions {
+        normalize: true,
+        ..ConvertOptions::default()
+    };
+    let bytes = convert_to_sfx_bytes(&p, &opts).unwrap();
+    let out: Vec<f32> = bytes[20..]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect();
+    let peak = out.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    let peak_db = 20.0 * peak.log10();
+    assert!((peak_db - -1.0).abs() < 0.01, "peak {} dBFS", peak_db);
+
+    // Shape is preserved: the ramp is just scaled.
+    let gain = out[0] / ramp[0];
+    assert!((out[100] - ramp[100] * gain).abs() < 1e-6);
+}
+
+#[test]
+fn normalize_leaves_silence_alone() {
+    let mut silent = vec![0.0f32; 64];
+    assert_eq!(normalize_peak(&mut silent, -1.0), 1.0);
+    assert!(silent.iter().all(|&s| s == 0.0));
+}
+
+fn sfx_f32_samples(bytes: &[u8]) -> Vec<f32> {
+    bytes[20..]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect()
+}
+
+#[test]
+fn mono_source_upmixes_to_stereo_by_duplication() {
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("mono.wav");
+    let mono: Vec<f32> = (0..100).map(|i| i as f32 / 200.0).collect();
+    File::create(&p)
+        .unwrap()
+        .write_all(&float_wav_bytes(48000, 1, &mono))
+        .unwrap();
+
+    let opts = ConvertOptions {
+        target_channels: Some(2),
+        ..ConvertOptions::default()
+    };
+    let bytes = convert_to_sfx_bytes(&p, &opts).unwrap();
+    assert_eq!(sfx_bytes_format(&bytes), Some((48000, 2)));
+    let out = sfx_f32_samples(&bytes);
+    assert_eq!(out.len(), 200);
+    for (frame, &m) in out.chunks_exact(2).zip(&mono) {
+        assert_eq!(frame, [m, m]);
+    }
+}
+
+#[test]
+fn stereo_source_downmixes_to_mono_by_averaging() {
+    let dir = tempdir().unwrap();
+    let p = dir.path().join("stereo.wav");
+    let stereo: Vec<f32> = (0..100).flat_map(|_| [0.5f32, -0.25]).collect();
+    File::create(&p)
+        .unwrap()
+        .write_all(&float_wav_bytes(48000, 2, &stereo))
+        .unwrap();
+
+    let opts = ConvertOptions {
+        target_channels: Some(1),
+        ..ConvertOptions::default()
+    };
+    let bytes = convert_to_sfx_bytes(&p, &opts).unwrap();
+    assert_eq!(sfx_bytes_format(&bytes), Some((48000, 1)));
+    let out = sfx_f32_samples(&bytes);
+    assert_eq!(out.len(), 100);
+    assert!(out.iter().all(|&s| (s - 0.125).abs() < 1e-6));
+}
+
+#[test]
+fn unsupported_remix_is_an_error() {
+    let err = remix_channels(&[0.0; 8], 2, 4).unwrap_err();
+    assert!(matches!(
+        err,
+        ConvertError::UnsupportedChannelLayout { from: 2, to: 4 }
+    ));
+    // 5.1 -> stereo is supported and never exceeds full scale.
+    let out = remix_channels(&[1.0, 1.0, 1.0, 1.0, 1.0, 1.0], 6, 2).unwrap();
+    assert_eq!(out.len(), 2);
+    assert!(out.iter().all(|&s| s <= 1.0 + 1e-6));
+}
+
+/// Stereo 44.1 kHz sine pair as 16-bit PCM.
+fn stereo_tone(frames: usize) -> Vec<i16> {
+    (0..frames)
+        .flat_map(|i| {
+            let t = i as f32 / 44100.0;
+            let l = (t * 440.0 * std::f32::consts::TAU).sin() * 0.5;
+            let r = (t * 660.0 * std::f32::consts::TAU).sin() * 0.25;
+            [(l * 32767.0) as i16, (r * 32767.0) as i16]
+        })
+        .collect()
+}
+
+fn max_deviation(a: &[f32], b: &[f32]) -> f32 {
+    a.iter()
+        .zip(b)
+        .fold(0.0f32, |m, (x, y)| m.max((x - y).abs()))
+}
+
+#[test]
+fn streamed_conversion_tracks_whole_buffer_resampling() {
+    let dir = tempdir().unwrap();
+    let wav = dir.path().join("long.wav");
+    let out_path = dir.path().join("long.sfx");
+
+    // The resampler is fed 4096-frame chunks: cover an exact multiple, one
+    // frame past it, and a multi-second clip ending mid-chunk.
+    for frames in [4096 * 3, 4096 * 3 + 1, 44100 * 2 + 777] {
+        let pcm = stereo_tone(frames);
+        File::create(&wav)
+            .unwrap()
+            .write_all(&wav_bytes(44100, 2, &pcm))
+            .unwrap();
+        {
+            let mut out = std::io::BufWriter::new(File::create(&out_path).unwrap());
+            convert_to_sfx_writer(&wav, &ConvertOptions::default(), &mut out).unwrap();
+        }
+        let streamed = std::fs::read(&out_path).unwrap();
+        assert_eq!(sfx_bytes_format(&streamed), Some((48000, 2)));
+
+        // Independent reference: whole-clip decode run through the
+        // single-pass resampler.
+        let input: Vec<f32> = pcm.iter().map(|&s| s as f32 / 32768.0).collect();
+        let reference =
+            resample_interleaved(&input, 44100, 48000, 2, ResampleQuality::High).unwrap();
+        let samples = sfx_f32_samples(&streamed);
+        assert_eq!(samples.len(), reference.len(), "{} input frames", frames);
+        let header_frames = u64::from_le_bytes(streamed[12..20].try_into().unwrap());
+        assert_eq!(header_frames as usize * 2, samples.len());
+        let err = max_deviation(&samples, &reference);
+        assert!(err < 1e-3, "{} frames: max deviation {}", frames, err);
+    }
+}
+
+#[test]
+fn streamed_conversion_applies_remix_normalize_loop_and_i16() {
+    let dir = tempdir().unwrap();
+    let wav = dir.path().join("long.wav");
+    let frames = 44100 * 2 + 777;
+    let pcm = stereo_tone(frames);
+    File::create(&wav)
+        .unwrap()
+        .write_all(&wav_bytes(44100, 2, &pcm))
+        .unwrap();
+
+    let options = ConvertOptions {
+        format: SfxFormat::I16,
+        loop_points: Some((1000, 80_000)),
+        normalize: true,
+        target_channels: Some(1),
+        ..ConvertOptions::default()
+    };
+    let mut out = std::io::Cursor::new(Vec::new());
+    convert_to_sfx_writer(&wav, &options, &mut out).unwrap();
+    let bytes = out.into_inner();
+    assert_eq!(&bytes[0..4], b"SFX2");
+    assert_eq!(bytes[4], 1, "i16 format byte");
+    let loop_start = u64::from_le_bytes(bytes[20..28].try_into().unwrap());
+    let loop_end = u64::from_le_bytes(bytes[28..36].try_into().unwrap());
+    assert_eq!((loop_start, loop_end), (1088, 87075));
+    let streamed: Vec<f32> = bytes[36..]
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32)
+        .collect();
+
+    let input: Vec<f32> = pcm.iter().map(|&s| s as f32 / 32768.0).collect();
+    let mut mono = remix_channels(&input, 2, 1).unwrap();
+    normalize_peak(&mut mono, -1.0);
+    let reference = resample_interleaved(&mono, 44100, 48000, 1, ResampleQuality::High).unwrap();
+    assert_eq!(streamed.len(), reference.len());
+    let err = max_deviation(&streamed, &reference);
+    assert!(err < 2e-3, "max deviation {}", err);
+}
+
+#[test]
+fn header_frame_count_ignores_format_and_loop_section() {
+    let dir = tempdir().unwrap();
+    let wav = dir.path().join("short.wav");
+    let pcm: Vec<i16> = (0..300)
+        .flat_map(|i| [i as i16 * 50, -(i as i16) * 50])
+        .collect();
+    File::create(&wav)
+        .unwrap()
+        .write_all(&wav_bytes(48000, 2, &pcm))
+        .unwrap();
+
+    let options = ConvertOptions {
+        format: SfxFormat::I16,
+        loop_points: Some((10, 200)),
+        ..ConvertOptions::default()
+    };
+    let bytes = convert_to_sfx_bytes(&wav, &options).unwrap();
+    assert_eq!(sfx_bytes_frames(&bytes), Some(300));
+    assert_eq!(bytes.len(), 36 + 300 * 2 * 2);
+    assert_eq!(sfx_bytes_frames(b"not an sfx blob at all"), None);
+}
@@ -0,0 +1,87 @@
+use asset_utils::{resample_interleaved, ResampleQuality};
+
+fn sine(frames: usize, rate: u32, freq: f32) -> Vec<f32> {
+    (0..frames)
+        .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / rate as f32).sin())
+        .collect()
+}
+
+#[test]
+fn every_quality_produces_the_same_output_length() {
+    let input = sine(4410, 44100, 440.0);
+    for quality in [
+        ResampleQuality::Fast,
+        ResampleQuality::Balanced,
+        ResampleQuality::High,
+    ] {
+        let out = resample_interleaved(&input, 44100, 48000, 1, quality).unwrap();
+        assert_eq!(out.len(), 4800, "{:?}", quality);
+        assert!(out.iter().all(|s| s.is_finite()));
+    }
+}
+
+#[test]
+fn quality_parses_from_cli_strings() {
+    assert_eq!("fast".parse(), Ok(ResampleQuality::Fast));
+    assert_eq!("Balanced".parse(), Ok(ResampleQuality::Balanced));
+    assert_eq!("HIGH".parse(), Ok(ResampleQuality::High));
+    assert!("ultra".parse::<ResampleQuality>().is_err());
+    assert_eq!(ResampleQuality::default(), ResampleQuality::High);
+}
+
+#[test]
+fn six_channel_resample_keeps_channels_independent() {
+    const CHANNELS: usize = 6;
+    let frames = 2205;
+    // Distinct tone per channel; channel 3 (LFE slot) stays silent to catch bleed.
+    let freqs = [220.0, 330.0, 440.0, 0.0, 550.0, 660.0];
+    let planar: Vec<Vec<f32>> = freqs
+        .iter()
+        .map(|&f| {
+            if f == 0.0 {
+                vec![0.0; frames]
+            } else {
+                sine(frames, 44100, f)
+            }
+        })
+        .collect();
+    let mut interleaved = vec![0.0f32; frames * CHANNELS];
+    for f in 0..frames {
+        for ch in 0..CHANNELS {
+            interleaved[f * CHANNELS + ch] = planar[ch][f];
+        }
+    }
+
+    let out =
+        resample_interleaved(&interleaved, 44100, 48000, CHANNELS, ResampleQuality::High).unwrap();
+    let out_frames = 2400;
+    assert_eq!(out.len(), out_frames * CHANNELS);
+
+    for (ch, mono_in) in planar.iter().enumerate() {
+        let expected =
+            resample_interleaved(mono_in, 44100, 48000, 1, ResampleQuality::High).unwrap();
+        assert_eq!(expected.len(), out_frames);
+        for f in 0..out_frames {
+            let got = out[f * CHANNELS + ch];
+            assert!(
+                (got - expected[f]).abs() < 1e-5,
+                "channel {} frame {}: {} != {}",
+                ch,
+                f,
+                got,
+                expected[f]
+            );
+        }
+    }
+    assert!((0..out_frames).all(|f| out[f * CHANNELS + 3] == 0.0));
+
+    // Away from the filter edges each tone should match its ideal 48 kHz rendering.
+    let ideal = sine(out_frames, 48000, 550.0);
+    for f in 256..out_frames - 256 {
+        assert!(
+            (out[f * CHANNELS + 4] - ideal[f]).abs() < 1e-2,
+            "frame {}",
+            f
+        );
+    }
+}